@@ -1,4 +1,15 @@
 pub mod resources;
 pub mod admin_menu;
 pub mod admin;
-pub mod admin_button;
\ No newline at end of file
+pub mod admin_button;
+pub mod tool;
+pub mod initiative_panel;
+pub mod token_movement;
+pub mod painting;
+pub mod notes_panel;
+pub mod status_bar;
+pub mod minimap;
+pub mod asset_report;
+pub mod tooltip;
+pub mod texture_search;
+pub mod palette;
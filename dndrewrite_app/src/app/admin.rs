@@ -5,40 +5,51 @@ use bevy::ui::RelativeCursorPosition;
 use bevy::utils::HashMap;
 use crate::app::resources::AdminButtonMarker;
 use crate::model::id::{Id, id};
+use crate::model::events::TexturesReloaded;
 use crate::model::resources::TextureTreeResource;
-use crate::model::texture_tree::TextureNode;
+use crate::model::texture_tree::{TextureLeaf, TextureNode};
+use hexx::Hex;
+use crate::map::attributes::{Hp, Size};
+use crate::map::combatant::Combatant;
+use crate::map::query::MapQuery;
+use crate::map::tile::OverlayKind;
 use crate::view::query::UIQuery;
+use crate::view::ui::{get_clicked_hex, get_hovered_hex};
 use super::*;
 
+/// Hit points of freshly placed tokens, until they're edited.
+const DEFAULT_HP: u16 = 10;
+
 impl FromWorld for resources::AdminMenus {
     fn from_world(world: &mut World) -> Self {
         fn recursive_helper(
             texture_node: TextureNode,
             admin_map: &mut HashMap<Id, Arc<admin_menu::AdminMenu>>,
             id: Id,
-        ) -> Option<Handle<Image>>
+        ) -> Option<TextureLeaf>
         {
             let branch;
             match texture_node.0 {
                 Ok(map) => branch = map,
-                Err(handle) => return Some(handle)
+                Err(leaf) => return Some(leaf)
             }
             let mut buttons = Vec::new();
             for (name, node) in branch {
-                let is_leaf = node.is_err();
-                if let Some(handle) = recursive_helper(node, admin_map, id.extend(name.clone())) {
-                    buttons.push(Arc::new(AdminButton {
-                        texture: handle,
+                let meta = node.meta().cloned().unwrap_or_default();
+                if let Some(leaf) = recursive_helper(node, admin_map, id.extend(name.clone())) {
+                    let order = meta.order.unwrap_or(i32::MAX);
+                    buttons.push((order, Arc::new(AdminButton {
+                        texture: leaf,
+                        tooltip: meta.tooltip(&name),
                         name: name.clone(),
-                        on_click: if !is_leaf {
-                            Box::new(move |id: Id| id.extend(name.clone()))
-                        } else {
-                            Box::new(|id: Id| id)
-                        },
+                        on_click: Box::new(move |id: Id| id.extend(name.clone())),
                         on_hover: Box::new(|mut window| window.cursor.icon = CursorIcon::Pointer),
-                    }));
+                    })));
                 }
             }
+            //Stable, so nodes without an order stay alphabetical
+            buttons.sort_by_key(|(order, _)| *order);
+            let buttons: Vec<_> = buttons.into_iter().map(|(_, button)| button).collect();
             let first_texture = buttons.first()
                 .map(|x| x.texture.clone());
             admin_map.insert(id, admin_menu::AdminMenu(buttons).into());
@@ -60,6 +71,23 @@ impl FromWorld for resources::CurrentAdminMenu {
     }
 }
 
+/// Rebuilds the texture menus after a reload, staying in the open menu if it
+/// still exists.
+pub fn rebuild_admin_menus(mut reloaded: EventReader<TexturesReloaded>, mut commands: Commands) {
+    if reloaded.read().count() == 0 { return; }
+    commands.add(|world: &mut World| {
+        let menus = resources::AdminMenus::from_world(world);
+        let mut stack = world.resource_mut::<resources::AdminMenuStack>();
+        if !menus.contains_key(&stack.0) {
+            stack.0 = id!();
+        }
+        let menu = menus[&stack.0].clone();
+        world.insert_resource(menus);
+        menu.render(world);
+        world.insert_resource(resources::CurrentAdminMenu(menu));
+    });
+}
+
 pub fn handle_admin(
     interaction_query: Query<(
         Entity,
@@ -71,6 +99,7 @@ pub fn handle_admin(
     mut ui: UIQuery,
     admin_menus: Res<resources::AdminMenus>,
     mut current_admin: ResMut<resources::CurrentAdminMenu>,
+    mut selected: ResMut<resources::SelectedTool>,
     mut commands: Commands,
 ) {
     let Some((_, mut window)) = ui.get_focused_window_mut() else { return; };
@@ -83,13 +112,25 @@ pub fn handle_admin(
         match *interaction {
             Interaction::Pressed => {
                 let id = (button.on_click)(admin_stack.0.clone());
-                if admin_stack.0 != id {
-                    let menu = admin_menus.get(&id).unwrap().clone();
-                    admin_stack.0 = id;
-                    let am = menu.clone();
-                    commands.add(move |w: &mut World| menu.render(w));
-                    **current_admin = am;
+                if admin_stack.0 == id { continue; }
+                //Leaves have no menu of their own, clicking them selects the texture
+                let Some(menu) = admin_menus.get(&id).cloned() else {
+                    let tool = tool::Tool::from_texture(id);
+                    info!("Selected tool {:?}.", tool);
+                    selected.0 = Some(tool);
+                    continue;
+                };
+                //Opening a tile family paints random variants from it, other
+                //branches are only navigated
+                let is_family = menu.0.iter().all(|button| !admin_menus.contains_key(&id.extend(button.name.clone())));
+                if !id.is_empty() && !OverlayKind::is_overlay(&id) && is_family {
+                    info!("Selected tile family '{}'.", id);
+                    selected.0 = Some(tool::Tool::Background(id.clone()));
                 }
+                admin_stack.0 = id;
+                let am = menu.clone();
+                commands.add(move |w: &mut World| menu.render(w));
+                **current_admin = am;
             }
             Interaction::Hovered => (button.on_hover)(window.reborrow()),
             _ => {}
@@ -98,4 +139,109 @@ pub fn handle_admin(
     if !is_over_any {
         window.cursor.icon = CursorIcon::Default;
     }
-}
\ No newline at end of file
+}
+
+pub fn use_tool(
+    ui: UIQuery,
+    keys: Res<ButtonInput<KeyCode>>,
+    selected: Res<resources::SelectedTool>,
+    interaction_query: Query<&Interaction, With<Node>>,
+    combatants: Query<(Entity, &Combatant)>,
+    mut selected_hex: ResMut<resources::SelectedHex>,
+    mut map: MapQuery,
+) {
+    let Some(tool) = selected.0.clone() else { return; };
+    //Clicks on the admin bar are not meant for the map
+    if interaction_query.iter().any(|interaction| *interaction != Interaction::None) { return; }
+    let Some(hex) = get_clicked_hex(ui, true) else { return; };
+    let erase = keys.any_pressed([KeyCode::ShiftLeft, KeyCode::ShiftRight]);
+    match tool {
+        tool::Tool::Overlay(_, id) => map.place_overlay(hex, id),
+        tool::Tool::Token(_, _) if erase => {
+            for (entity, combatant) in combatants.iter().filter(|(_, combatant)| combatant.hex() == hex) {
+                map.despawn_combatant(entity, combatant.clone());
+            }
+        }
+        tool::Tool::Token(id, combatant_type) => {
+            let name = Combatant::name_from_texture(&id);
+            let combatant = Combatant::new(name, id, Size::Medium, Hp::new(DEFAULT_HP), combatant_type);
+            map.spawn_combatant(hex, combatant);
+        }
+        tool::Tool::Inspect => selected_hex.0 = Some(hex),
+        tool::Tool::Background(_) | tool::Tool::Linear(..) | tool::Tool::Fog | tool::Tool::Move | tool::Tool::Ruler => {}
+    }
+}
+
+pub fn draw_stroke(
+    mut ui: UIQuery,
+    keys: Res<ButtonInput<KeyCode>>,
+    mouse_button: Res<ButtonInput<MouseButton>>,
+    selected: Res<resources::SelectedTool>,
+    interaction_query: Query<&Interaction, With<Node>>,
+    mut stroke: ResMut<resources::Stroke>,
+    mut map: MapQuery,
+) {
+    let Some(tool @ (tool::Tool::Linear(..) | tool::Tool::Fog)) = &selected.0 else { return; };
+    if !mouse_button.pressed(MouseButton::Left) {
+        stroke.0 = None;
+        return;
+    }
+    //Strokes have to start on the map, not on the admin bar
+    let starting = mouse_button.just_pressed(MouseButton::Left);
+    if starting && interaction_query.iter().any(|interaction| *interaction != Interaction::None) { return; }
+    if !starting && stroke.0.is_none() { return; }
+    let Some(hex) = get_hovered_hex(&mut ui, true) else { return; };
+    let erase = keys.any_pressed([KeyCode::ShiftLeft, KeyCode::ShiftRight]);
+    match tool {
+        tool::Tool::Linear(_, family) => {
+            if let Some(last) = stroke.0.filter(|last| *last != hex) {
+                //Fast strokes skip hexes, fill them in so every segment is between neighbours
+                let line: Vec<Hex> = last.line_to(hex).collect();
+                for pair in line.windows(2) {
+                    map.connect_linear(family, (pair[0], pair[1]), !erase);
+                }
+            }
+        }
+        tool::Tool::Fog => map.set_revealed(hex, !erase),
+        _ => unreachable!(),
+    }
+    stroke.0 = Some(hex);
+}
+
+pub fn tool_shortcuts(
+    keys: Res<ButtonInput<KeyCode>>,
+    input: Res<resources::InitiativeInput>,
+    mut selected: ResMut<resources::SelectedTool>,
+) {
+    if input.is_editing() { return; }
+    let tool = if keys.just_pressed(KeyCode::KeyF) {
+        tool::Tool::Fog
+    } else if keys.just_pressed(KeyCode::KeyM) {
+        tool::Tool::Move
+    } else if keys.just_pressed(KeyCode::KeyE) {
+        tool::Tool::Inspect
+    } else if keys.just_pressed(KeyCode::KeyR) {
+        tool::Tool::Ruler
+    } else if keys.just_pressed(KeyCode::KeyT) {
+        let Some(tool) = selected.0.as_ref().and_then(tool::Tool::next_figure_tool) else { return; };
+        tool
+    } else {
+        return;
+    };
+    info!("Selected tool {:?}.", tool);
+    selected.0 = Some(tool);
+}
+
+pub fn deselect_tool(
+    mouse_button: Res<ButtonInput<MouseButton>>,
+    mut selected: ResMut<resources::SelectedTool>,
+) {
+    if mouse_button.just_pressed(MouseButton::Right) && selected.0.take().is_some() {
+        info!("Deselected tool.");
+    }
+}
+
+/// The mouse pans the map unless it's busy with a tool.
+pub fn no_tool_selected(selected: Option<Res<resources::SelectedTool>>) -> bool {
+    selected.is_none_or(|selected| selected.0.is_none())
+}
@@ -1,9 +1,12 @@
 use bevy::prelude::*;
 use crate::model::id::Id;
+use crate::model::texture_tree::TextureLeaf;
 
 pub struct AdminButton {
-    pub texture: Handle<Image>,
+    pub texture: TextureLeaf,
     pub name: String,
+    /// Shown while the button is hovered.
+    pub tooltip: String,
     pub on_click: Box<dyn Fn(Id) -> Id + Send + Sync>,
     pub on_hover: Box<dyn Fn(Mut<Window>) -> () + Send + Sync>,
 }
\ No newline at end of file
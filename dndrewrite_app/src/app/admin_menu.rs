@@ -26,9 +26,10 @@ impl AdminMenu {
                         height: Val::Vh(10.),
                         ..default()
                     },
-                    image: UiImage::new(admin_button.texture.clone()),
+                    image: UiImage::new(admin_button.texture.image.clone()),
                     ..default()
                 },
+                admin_button.texture.atlas.clone(),
                 Interaction::default(),
                 RelativeCursorPosition::default(),
                 AdminButtonMarker(admin_button.clone())))
@@ -0,0 +1,90 @@
+use bevy::prelude::*;
+use crate::app::resources::{AssetReportButton, AssetReportPanel};
+use crate::model::resources::AssetReport;
+
+const FONT_SIZE: f32 = 18.;
+const WARNING_COLOR: Color = Color::srgb(1., 0.6, 0.3);
+/// Problems listed at most, the rest are only in the log file.
+const MAX_LISTED: usize = 40;
+
+pub fn spawn_asset_report(commands: &mut Commands, admin_camera: Entity) {
+    let header = commands.spawn((TextBundle::from_section("", TextStyle {
+        font_size: FONT_SIZE,
+        color: WARNING_COLOR,
+        ..default()
+    }), Interaction::default(), AssetReportButton)).id();
+    let list = commands.spawn(NodeBundle {
+        style: Style {
+            display: Display::None,
+            flex_direction: FlexDirection::Column,
+            margin: UiRect::top(Val::Px(4.)),
+            ..default()
+        },
+        ..default()
+    }).id();
+    let panel = commands.spawn((NodeBundle {
+        style: Style {
+            position_type: PositionType::Absolute,
+            right: Val::Px(8.),
+            bottom: Val::Vh(11.),
+            max_width: Val::Vw(30.),
+            max_height: Val::Vh(40.),
+            flex_direction: FlexDirection::Column,
+            padding: UiRect::all(Val::Px(6.)),
+            overflow: Overflow::clip_y(),
+            ..default()
+        },
+        background_color: BackgroundColor(Color::srgba(0., 0., 0., 0.75)),
+        ..default()
+    }, Interaction::default(), TargetCamera(admin_camera))).push_children(&[header, list]).id();
+    commands.insert_resource(AssetReportPanel { panel, header, list, expanded: false });
+}
+
+pub fn toggle_asset_report(
+    buttons: Query<Ref<Interaction>, With<AssetReportButton>>,
+    mut panel: ResMut<AssetReportPanel>,
+) {
+    for interaction in &buttons {
+        if interaction.is_changed() && *interaction == Interaction::Pressed {
+            panel.expanded = !panel.expanded;
+        }
+    }
+}
+
+/// Shows the loader's problems, hidden while there are none.
+pub fn render_asset_report(
+    report: Res<AssetReport>,
+    panel: Res<AssetReportPanel>,
+    mut styles: Query<&mut Style>,
+    mut texts: Query<&mut Text>,
+    mut commands: Commands,
+) {
+    if !report.is_changed() && !panel.is_changed() { return; }
+    let errors = report.errors();
+    if let Ok(mut style) = styles.get_mut(panel.panel) {
+        style.display = if errors.is_empty() { Display::None } else { Display::Flex };
+    }
+    if let Ok(mut text) = texts.get_mut(panel.header) {
+        let fold = if panel.expanded { "v" } else { ">" };
+        text.sections[0].value = format!("{} {} asset problems, see asset_report.log", fold, errors.len());
+    }
+    if let Ok(mut style) = styles.get_mut(panel.list) {
+        style.display = if panel.expanded { Display::Flex } else { Display::None };
+    }
+    if !report.is_changed() { return; }
+    commands.entity(panel.list).despawn_descendants();
+    let mut rows: Vec<Entity> = errors.iter()
+        .take(MAX_LISTED)
+        .map(|error| commands.spawn(TextBundle::from_section(error.to_string(), TextStyle {
+            font_size: FONT_SIZE,
+            ..default()
+        })).id())
+        .collect();
+    if errors.len() > MAX_LISTED {
+        rows.push(commands.spawn(TextBundle::from_section(format!("... and {} more", errors.len() - MAX_LISTED), TextStyle {
+            font_size: FONT_SIZE,
+            ..default()
+        })).id());
+    }
+    commands.entity(panel.list).push_children(&rows);
+}
@@ -0,0 +1,178 @@
+use bevy::prelude::*;
+use crate::app::resources::{InitiativeInput, InitiativePanel, InitiativeRow};
+use crate::map::combatant::Combatant;
+use crate::map::resources::Initiative;
+use crate::view::scrolling_list::ScrollingList;
+
+const ACTIVE_COLOR: Color = Color::srgb(1., 0.85, 0.3);
+const EDITING_COLOR: Color = Color::srgb(0.5, 0.8, 1.);
+const FONT_SIZE: f32 = 24.;
+
+pub fn spawn_initiative_panel(commands: &mut Commands, admin_camera: Entity) {
+    let list = commands.spawn((NodeBundle {
+        style: Style {
+            display: Display::Flex,
+            flex_direction: FlexDirection::Column,
+            padding: UiRect::all(Val::Px(8.)),
+            ..default()
+        },
+        ..default()
+    }, ScrollingList::vertical())).id();
+    commands.spawn((NodeBundle {
+        style: Style {
+            position_type: PositionType::Absolute,
+            right: Val::Px(0.),
+            top: Val::Px(0.),
+            width: Val::Vw(20.),
+            max_height: Val::Vh(80.),
+            overflow: Overflow::clip_y(),
+            ..default()
+        },
+        background_color: BackgroundColor(Color::srgba(0., 0., 0., 0.6)),
+        ..default()
+    }, Interaction::default(), TargetCamera(admin_camera))).push_children(&[list]);
+    commands.insert_resource(InitiativePanel(list));
+    commands.init_resource::<InitiativeInput>();
+}
+
+pub fn render_initiative(
+    panel: Res<InitiativePanel>,
+    initiative: Res<Initiative>,
+    input: Res<InitiativeInput>,
+    combatants: Query<&Combatant>,
+    mut commands: Commands,
+) {
+    if !initiative.is_changed() && !input.is_changed() { return; }
+    commands.entity(**panel).despawn_descendants();
+    let header = match initiative.round() {
+        0 => "Initiative".to_string(),
+        round => format!("Round {}", round),
+    };
+    let mut rows = vec![commands.spawn(TextBundle::from_section(header, TextStyle {
+        font_size: FONT_SIZE,
+        ..default()
+    })).id()];
+    for entry in initiative.entries() {
+        let name = combatants.get(entry.combatant()).map_or("?", Combatant::name);
+        let is_active = initiative.active_combatant() == Some(entry.combatant());
+        let is_editing = input.combatant == Some(entry.combatant());
+        let (total, color) = match (is_editing, is_active) {
+            (true, _) => (format!("{}_", input.buffer), EDITING_COLOR),
+            (false, true) => (entry.total().to_string(), ACTIVE_COLOR),
+            (false, false) => (entry.total().to_string(), Color::WHITE),
+        };
+        rows.push(commands.spawn((
+            TextBundle::from_section(format!("{:>3}  {}", total, name), TextStyle {
+                font_size: FONT_SIZE,
+                color,
+                ..default()
+            }),
+            Interaction::default(),
+            InitiativeRow(entry.combatant()),
+        )).id());
+    }
+    commands.entity(**panel).push_children(&rows);
+}
+
+pub fn select_initiative_row(
+    rows: Query<(Ref<Interaction>, &InitiativeRow)>,
+    mut input: ResMut<InitiativeInput>,
+) {
+    for (interaction, row) in &rows {
+        if interaction.is_changed() && *interaction == Interaction::Pressed {
+            input.combatant = Some(**row);
+            input.buffer.clear();
+        }
+    }
+}
+
+pub fn initiative_shortcuts(
+    keys: Res<ButtonInput<KeyCode>>,
+    combatants: Query<(Entity, &Combatant)>,
+    mut initiative: ResMut<Initiative>,
+    mut input: ResMut<InitiativeInput>,
+) {
+    let shift = keys.any_pressed([KeyCode::ShiftLeft, KeyCode::ShiftRight]);
+    if let Some(combatant) = input.combatant {
+        for key in keys.get_just_pressed() {
+            match key {
+                KeyCode::Enter | KeyCode::NumpadEnter => {
+                    if let Ok(total) = input.buffer.parse() {
+                        let modifier = combatants.get(combatant).map_or(0, |(_, c)| c.initiative_bonus());
+                        initiative.set(combatant, total, modifier);
+                    }
+                    *input = InitiativeInput::default();
+                    return;
+                }
+                KeyCode::Escape => {
+                    *input = InitiativeInput::default();
+                    return;
+                }
+                KeyCode::Backspace => {
+                    input.buffer.pop();
+                }
+                KeyCode::Minus | KeyCode::NumpadSubtract if input.buffer.is_empty() => input.buffer.push('-'),
+                key => if let Some(digit) = digit(*key) {
+                    input.buffer.push(digit);
+                }
+            }
+        }
+        return;
+    }
+    if keys.just_pressed(KeyCode::KeyI) {
+        if shift {
+            info!("Encounter over.");
+            initiative.clear();
+            return;
+        }
+        for (entity, combatant) in &combatants {
+            if initiative.contains(entity) { continue; }
+            let total = initiative.roll(entity, combatant.initiative_bonus());
+            info!("'{}' rolled {} for initiative.", combatant.name(), total);
+        }
+    }
+    if keys.just_pressed(KeyCode::KeyN) {
+        if shift {
+            initiative.previous_turn();
+        } else {
+            initiative.next_turn();
+        }
+    }
+}
+
+fn digit(key: KeyCode) -> Option<char> {
+    let digit = match key {
+        KeyCode::Digit0 | KeyCode::Numpad0 => 0,
+        KeyCode::Digit1 | KeyCode::Numpad1 => 1,
+        KeyCode::Digit2 | KeyCode::Numpad2 => 2,
+        KeyCode::Digit3 | KeyCode::Numpad3 => 3,
+        KeyCode::Digit4 | KeyCode::Numpad4 => 4,
+        KeyCode::Digit5 | KeyCode::Numpad5 => 5,
+        KeyCode::Digit6 | KeyCode::Numpad6 => 6,
+        KeyCode::Digit7 | KeyCode::Numpad7 => 7,
+        KeyCode::Digit8 | KeyCode::Numpad8 => 8,
+        KeyCode::Digit9 | KeyCode::Numpad9 => 9,
+        _ => return None,
+    };
+    char::from_digit(digit, 10)
+}
+
+pub fn highlight_active_combatant(
+    initiative: Res<Initiative>,
+    mut tokens: Query<(Entity, &mut Sprite), With<Combatant>>,
+) {
+    if !initiative.is_changed() { return; }
+    let active = initiative.active_combatant();
+    for (entity, mut sprite) in &mut tokens {
+        sprite.color = if Some(entity) == active { ACTIVE_COLOR } else { Color::WHITE };
+    }
+}
+
+pub fn forget_removed_combatants(
+    mut removed: RemovedComponents<Combatant>,
+    mut initiative: ResMut<Initiative>,
+) {
+    for entity in removed.read() {
+        initiative.remove(entity);
+    }
+}
@@ -0,0 +1,237 @@
+use bevy::prelude::*;
+use bevy::render::render_asset::RenderAssetUsages;
+use bevy::render::render_resource::{Extent3d, TextureDimension, TextureFormat};
+use bevy::render::texture::ImageSampler;
+use bevy::ui::RelativeCursorPosition;
+use hexx::Hex;
+use crate::app::resources::{Minimap, MinimapBounds};
+use crate::map::brush::to_offset;
+use crate::map::resources::Map;
+use crate::model::id::Id;
+use crate::view::query::UIQuery;
+use crate::view::resources::HexLayoutResource;
+
+const ADMIN_VIEW_COLOR: Color = Color::srgb(1., 0.85, 0.3);
+const USER_VIEW_COLOR: Color = Color::srgb(0.4, 0.9, 1.);
+const EMPTY: [u8; 4] = [0, 0, 0, 0];
+
+/// A rough colour for every tile family, enough to tell land from sea.
+fn tile_color(id: &Id) -> [u8; 4] {
+    let tile_type = id.get(0).unwrap_or_default();
+    let mut color = match tile_type {
+        "ocean" => Vec3::new(0.15, 0.3, 0.6),
+        "desert" => Vec3::new(0.85, 0.75, 0.45),
+        "swamp" => Vec3::new(0.35, 0.4, 0.25),
+        "jungle_forest" => Vec3::new(0.1, 0.45, 0.2),
+        "hills" => Vec3::new(0.5, 0.55, 0.3),
+        "city" => Vec3::new(0.6, 0.45, 0.4),
+        "clouds" => Vec3::splat(0.8),
+        forest if forest.ends_with("forest") => Vec3::new(0.15, 0.4, 0.15),
+        _ => Vec3::new(0.45, 0.65, 0.3),
+    };
+    match id.get(1) {
+        Some("mountain") if tile_type != "ocean" => color = color.lerp(Vec3::splat(0.5), 0.6),
+        Some("lake") => color = color.lerp(Vec3::new(0.2, 0.4, 0.8), 0.4),
+        _ => {}
+    }
+    if id.get(2) == Some("winter") {
+        color = color.lerp(Vec3::ONE, 0.6);
+    }
+    Color::srgb(color.x, color.y, color.z).to_srgba().to_u8_array()
+}
+
+fn minimap_image(size: UVec2) -> Image {
+    let mut image = Image::new_fill(
+        Extent3d {
+            width: size.x.max(1),
+            height: size.y.max(1),
+            depth_or_array_layers: 1,
+        },
+        TextureDimension::D2,
+        &EMPTY,
+        TextureFormat::Rgba8UnormSrgb,
+        RenderAssetUsages::default(),
+    );
+    //Blurring would smear the few pixels a tile gets
+    image.sampler = ImageSampler::nearest();
+    image
+}
+
+impl MinimapBounds {
+    fn size(self) -> UVec2 {
+        UVec2::new(self.columns * 2 + 1, self.rows)
+    }
+
+    fn pixel(self, hex: Hex) -> UVec2 {
+        let (column, row) = to_offset(hex);
+        UVec2::new(
+            ((column - self.left) * 2 + (row & 1)) as u32,
+            (self.bottom - row) as u32,
+        )
+    }
+}
+
+fn bounds(map: &Map) -> MinimapBounds {
+    let offsets: Vec<(i32, i32)> = map.hexes().map(to_offset).collect();
+    let left = offsets.iter().map(|(column, _)| *column).min().unwrap_or_default();
+    let right = offsets.iter().map(|(column, _)| *column).max().unwrap_or_default();
+    let top = offsets.iter().map(|(_, row)| *row).min().unwrap_or_default();
+    let bottom = offsets.iter().map(|(_, row)| *row).max().unwrap_or_default();
+    MinimapBounds {
+        left,
+        bottom,
+        columns: (right - left + 1) as u32,
+        rows: (bottom - top + 1) as u32,
+    }
+}
+
+/// World position of a fractional pixel of the minimap, and the other way around.
+struct Projection {
+    origin: Vec2,
+    column: f32,
+    row: f32,
+    bounds: MinimapBounds,
+}
+
+impl Projection {
+    fn new(layout: &HexLayoutResource, bounds: MinimapBounds) -> Self {
+        let origin = layout.hex_to_world_pos(Hex::ZERO);
+        Self {
+            origin,
+            column: layout.hex_to_world_pos(Hex::new(1, 0)).x - origin.x,
+            row: layout.hex_to_world_pos(Hex::new(0, 1)).y - origin.y,
+            bounds,
+        }
+    }
+
+    fn to_pixel(&self, position: Vec2) -> Vec2 {
+        let relative = position - self.origin;
+        Vec2::new(
+            (relative.x / self.column - self.bounds.left as f32) * 2. + 1.,
+            self.bounds.bottom as f32 - relative.y / self.row + 0.5,
+        )
+    }
+
+    fn to_world(&self, pixel: Vec2) -> Vec2 {
+        let column = (pixel.x - 1.) / 2. + self.bounds.left as f32;
+        let row = self.bounds.bottom as f32 + 0.5 - pixel.y;
+        self.origin + Vec2::new(column * self.column, row * self.row)
+    }
+}
+
+pub fn spawn_minimap(commands: &mut Commands, images: &mut Assets<Image>, admin_camera: Entity) {
+    let image = images.add(minimap_image(UVec2::ONE));
+    let view = |commands: &mut Commands, color: Color| commands.spawn(NodeBundle {
+        style: Style {
+            position_type: PositionType::Absolute,
+            border: UiRect::all(Val::Px(1.)),
+            ..default()
+        },
+        border_color: BorderColor(color),
+        ..default()
+    }).id();
+    let admin_view = view(commands, ADMIN_VIEW_COLOR);
+    let user_view = view(commands, USER_VIEW_COLOR);
+    let node = commands.spawn((ImageBundle {
+        style: Style {
+            position_type: PositionType::Absolute,
+            left: Val::Px(8.),
+            bottom: Val::Vh(11.),
+            width: Val::Vw(16.),
+            overflow: Overflow::clip(),
+            ..default()
+        },
+        image: UiImage::new(image.clone()),
+        background_color: BackgroundColor(Color::WHITE),
+        ..default()
+    }, Interaction::default(), RelativeCursorPosition::default(), TargetCamera(admin_camera)))
+        .push_children(&[admin_view, user_view])
+        .id();
+    commands.insert_resource(Minimap {
+        image,
+        node,
+        admin_view,
+        user_view,
+        bounds: MinimapBounds::default(),
+    });
+}
+
+/// Redraws the minimap whenever the map changes.
+pub fn render_minimap(
+    map: Res<Map>,
+    layout: Res<HexLayoutResource>,
+    mut minimap: ResMut<Minimap>,
+    mut images: ResMut<Assets<Image>>,
+    mut styles: Query<&mut Style>,
+) {
+    if !map.is_changed() { return; }
+    let bounds = bounds(&map);
+    let mut image = minimap_image(bounds.size());
+    let width = image.width();
+    for hex in map.hexes() {
+        let Some(background) = map.background(hex) else { continue; };
+        let pixel = bounds.pixel(hex);
+        let color = tile_color(background);
+        for x in [pixel.x, pixel.x + 1] {
+            let start = ((pixel.y * width + x) * 4) as usize;
+            image.data[start..start + 4].copy_from_slice(&color);
+        }
+    }
+    images.insert(&minimap.image, image);
+    if minimap.bounds != bounds {
+        minimap.bounds = bounds;
+        if let Ok(mut style) = styles.get_mut(minimap.node) {
+            //Rows of pointy hexes are closer together than their columns
+            let projection = Projection::new(&layout, bounds);
+            let size = bounds.size().as_vec2() * Vec2::new(projection.column / 2., projection.row.abs());
+            style.aspect_ratio = Some(size.x / size.y);
+        }
+    }
+}
+
+/// Frames what each window's camera shows.
+pub fn update_minimap_views(
+    ui: UIQuery,
+    minimap: Res<Minimap>,
+    layout: Res<HexLayoutResource>,
+    mut styles: Query<&mut Style>,
+) {
+    let projection = Projection::new(&layout, minimap.bounds);
+    let size = minimap.bounds.size().as_vec2();
+    for (admin, view) in [(true, minimap.admin_view), (false, minimap.user_view)] {
+        let Ok(mut style) = styles.get_mut(view) else { continue; };
+        let Some(visible) = ui.visible_world_rect(admin) else {
+            if style.display != Display::None {
+                style.display = Display::None;
+            }
+            continue;
+        };
+        let rect = Rect::from_corners(projection.to_pixel(visible.min), projection.to_pixel(visible.max));
+        let (min, max) = (rect.min / size * 100., rect.max / size * 100.);
+        let (left, top) = (Val::Percent(min.x), Val::Percent(min.y));
+        let (width, height) = (Val::Percent(max.x - min.x), Val::Percent(max.y - min.y));
+        //Touching the style lays the UI out again, so only when the view moved
+        if (style.display, style.left, style.top, style.width, style.height) != (Display::Flex, left, top, width, height) {
+            style.display = Display::Flex;
+            (style.left, style.top) = (left, top);
+            (style.width, style.height) = (width, height);
+        }
+    }
+}
+
+/// Clicking the minimap moves the admin camera there, or the players' with shift.
+pub fn jump_to_minimap(
+    mut ui: UIQuery,
+    keys: Res<ButtonInput<KeyCode>>,
+    minimap: Res<Minimap>,
+    layout: Res<HexLayoutResource>,
+    nodes: Query<(&Interaction, &RelativeCursorPosition)>,
+) {
+    let Ok((interaction, cursor)) = nodes.get(minimap.node) else { return; };
+    if *interaction != Interaction::Pressed { return; }
+    let Some(position) = cursor.normalized.filter(|_| cursor.mouse_over()) else { return; };
+    let projection = Projection::new(&layout, minimap.bounds);
+    let world = projection.to_world(position * minimap.bounds.size().as_vec2());
+    let admin = !keys.any_pressed([KeyCode::ShiftLeft, KeyCode::ShiftRight]);
+    ui.center_on_world(admin, world);
+}
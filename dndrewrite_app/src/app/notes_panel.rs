@@ -0,0 +1,328 @@
+use bevy::input::keyboard::{Key, KeyboardInput};
+use bevy::prelude::*;
+use crate::app::resources::{NoteButton, NoteEditor, NoteField, NotesPanel, SelectedHex, TextureSearch};
+use crate::map::note::Note;
+use crate::map::query::MapQuery;
+use crate::map::resources::Map;
+use crate::view::query::UIQuery;
+use crate::view::resources::HexLayoutResource;
+
+const EDITING_COLOR: Color = Color::srgb(0.5, 0.8, 1.);
+const MUTED_COLOR: Color = Color::srgb(0.6, 0.6, 0.6);
+const SELECTED_COLOR: Color = Color::srgb(1., 0.85, 0.3);
+const FONT_SIZE: f32 = 20.;
+const HEADING_SIZE: f32 = 26.;
+const MAX_RESULTS: usize = 10;
+
+pub fn spawn_notes_panel(commands: &mut Commands, admin_camera: Entity) {
+    let column = |commands: &mut Commands| commands.spawn(NodeBundle {
+        style: Style {
+            display: Display::Flex,
+            flex_direction: FlexDirection::Column,
+            padding: UiRect::all(Val::Px(8.)),
+            row_gap: Val::Px(4.),
+            ..default()
+        },
+        ..default()
+    }).id();
+    let search = column(commands);
+    let inspector = column(commands);
+    commands.spawn((NodeBundle {
+        style: Style {
+            position_type: PositionType::Absolute,
+            left: Val::Px(0.),
+            top: Val::Px(0.),
+            width: Val::Vw(22.),
+            max_height: Val::Vh(80.),
+            flex_direction: FlexDirection::Column,
+            overflow: Overflow::clip_y(),
+            ..default()
+        },
+        background_color: BackgroundColor(Color::srgba(0., 0., 0., 0.6)),
+        ..default()
+    }, Interaction::default(), TargetCamera(admin_camera))).push_children(&[search, inspector]);
+    commands.insert_resource(NotesPanel { search, inspector });
+    commands.init_resource::<NoteEditor>();
+    commands.init_resource::<SelectedHex>();
+}
+
+/// Shortcuts stay quiet while a note field or the texture search has the keyboard.
+pub fn not_typing(editor: Option<Res<NoteEditor>>, search: Option<Res<TextureSearch>>) -> bool {
+    editor.is_none_or(|editor| !editor.is_editing())
+        && search.is_none_or(|search| !search.is_editing())
+}
+
+/// Saves the draft to its hex, empty notes are removed.
+fn commit(editor: &NoteEditor, map: &mut MapQuery) {
+    let Some(hex) = editor.hex else { return; };
+    let note = Some(editor.draft.clone()).filter(|note| !note.is_empty());
+    if map.map.note(hex) != note.as_ref() {
+        map.set_note(hex, note);
+    }
+}
+
+/// Gives the keyboard back, keeping what was typed into the note.
+pub fn stop_editing(editor: &mut NoteEditor, map: &mut MapQuery) {
+    if editor.field.is_some_and(|field| field != NoteField::Search) {
+        commit(editor, map);
+    }
+    editor.field = None;
+}
+
+/// Keeps the draft in step with the selected hex and with undo.
+pub fn sync_note_draft(
+    selected: Res<SelectedHex>,
+    mut editor: ResMut<NoteEditor>,
+    mut map: MapQuery,
+) {
+    if editor.hex != **selected {
+        if editor.field.is_some_and(|field| field != NoteField::Search) {
+            commit(&editor, &mut map);
+            editor.field = None;
+        }
+        editor.hex = **selected;
+    }
+    if editor.field.is_some_and(|field| field != NoteField::Search) { return; }
+    let Some(hex) = editor.hex else { return; };
+    let note = map.map.note(hex).cloned().unwrap_or_default();
+    if editor.draft != note {
+        editor.draft = note;
+    }
+}
+
+pub fn note_buttons(
+    buttons: Query<(Ref<Interaction>, &NoteButton)>,
+    mut editor: ResMut<NoteEditor>,
+    mut selected: ResMut<SelectedHex>,
+    mut ui: UIQuery,
+    mut map: MapQuery,
+) {
+    for (interaction, button) in &buttons {
+        if !interaction.is_changed() || *interaction != Interaction::Pressed { continue; }
+        if editor.field.is_some_and(|field| field != NoteField::Search) {
+            commit(&editor, &mut map);
+        }
+        match *button {
+            NoteButton::Field(field) => editor.field = Some(field),
+            NoteButton::Visibility => {
+                editor.draft.visibility = editor.draft.visibility.toggled();
+                commit(&editor, &mut map);
+                editor.field = None;
+            }
+            NoteButton::Delete => {
+                editor.draft = Note::default();
+                commit(&editor, &mut map);
+                editor.field = None;
+            }
+            NoteButton::Result(hex) => {
+                **selected = Some(hex);
+                ui.center_on(true, hex);
+                editor.field = None;
+            }
+        }
+    }
+}
+
+pub fn type_note(
+    mut keyboard: EventReader<KeyboardInput>,
+    keys: Res<ButtonInput<KeyCode>>,
+    mut editor: ResMut<NoteEditor>,
+    mut selected: ResMut<SelectedHex>,
+    mut ui: UIQuery,
+    mut map: MapQuery,
+) {
+    let Some(field) = editor.field else {
+        keyboard.clear();
+        return;
+    };
+    //Ctrl combinations are shortcuts, not text
+    let control = keys.any_pressed([KeyCode::ControlLeft, KeyCode::ControlRight]);
+    for event in keyboard.read() {
+        if !event.state.is_pressed() || control { continue; }
+        match &event.logical_key {
+            Key::Escape => {
+                if field == NoteField::Search {
+                    editor.search.clear();
+                } else {
+                    commit(&editor, &mut map);
+                }
+                editor.field = None;
+                return;
+            }
+            Key::Enter if field == NoteField::Search => {
+                let Some((hex, _)) = map.map.search_notes(&editor.search).first().copied() else { continue; };
+                **selected = Some(hex);
+                ui.center_on(true, hex);
+                editor.field = None;
+                return;
+            }
+            Key::Enter if field == NoteField::Body => editor.draft.body.push('\n'),
+            Key::Enter => {
+                commit(&editor, &mut map);
+                editor.field = None;
+                return;
+            }
+            Key::Backspace => {
+                match field {
+                    NoteField::Title => editor.draft.title.pop(),
+                    NoteField::Body => editor.draft.body.pop(),
+                    NoteField::Search => editor.search.pop(),
+                    NoteField::Tags => {
+                        let mut tags = editor.draft.tags.join(", ");
+                        tags.pop();
+                        editor.draft.tags = parse_tags(&tags);
+                        None
+                    }
+                };
+            }
+            Key::Space => push_text(&mut editor, field, " "),
+            Key::Character(text) => push_text(&mut editor, field, text),
+            _ => {}
+        }
+    }
+}
+
+/// Tags are typed as one comma separated line.
+fn parse_tags(text: &str) -> Vec<String> {
+    let mut tags: Vec<String> = text.split(',').map(|tag| tag.trim_start().to_string()).collect();
+    if tags.iter().all(String::is_empty) {
+        tags.clear();
+    }
+    tags
+}
+
+fn push_text(editor: &mut NoteEditor, field: NoteField, text: &str) {
+    match field {
+        NoteField::Title => editor.draft.title.push_str(text),
+        NoteField::Body => editor.draft.body.push_str(text),
+        NoteField::Search => editor.search.push_str(text),
+        NoteField::Tags => {
+            let tags = editor.draft.tags.join(", ") + text;
+            editor.draft.tags = parse_tags(&tags);
+        }
+    }
+}
+
+fn label(text: impl Into<String>, font_size: f32, color: Color) -> TextBundle {
+    TextBundle::from_section(text, TextStyle {
+        font_size,
+        color,
+        ..default()
+    })
+}
+
+/// Headings and list items of the markdown body, the rest is shown as written.
+fn markdown(body: &str, color: Color) -> Text {
+    let sections = body.lines()
+        .map(|line| {
+            let heading = line.trim_start_matches('#');
+            if heading.len() < line.len() {
+                return TextSection::new(format!("{}\n", heading.trim()), TextStyle {
+                    font_size: HEADING_SIZE,
+                    color,
+                    ..default()
+                });
+            }
+            let line = match line.strip_prefix("- ").or_else(|| line.strip_prefix("* ")) {
+                Some(item) => format!("  • {}\n", item),
+                None => format!("{}\n", line),
+            };
+            TextSection::new(line, TextStyle {
+                font_size: FONT_SIZE,
+                color,
+                ..default()
+            })
+        })
+        .collect::<Vec<_>>();
+    Text::from_sections(sections)
+}
+
+pub fn render_notes_panel(
+    panel: Res<NotesPanel>,
+    editor: Res<NoteEditor>,
+    map: Res<Map>,
+    mut commands: Commands,
+) {
+    if !editor.is_changed() && !map.is_changed() { return; }
+    let cursor = |field: NoteField| if editor.field == Some(field) { "_" } else { "" };
+    let color = |field: NoteField| if editor.field == Some(field) { EDITING_COLOR } else { Color::WHITE };
+
+    commands.entity(panel.search).despawn_descendants();
+    let mut rows = vec![commands.spawn((
+        label(format!("Search: {}{}", editor.search, cursor(NoteField::Search)), FONT_SIZE, color(NoteField::Search)),
+        Interaction::default(),
+        NoteButton::Field(NoteField::Search),
+    )).id()];
+    if !editor.search.is_empty() {
+        for (hex, note) in map.search_notes(&editor.search).into_iter().take(MAX_RESULTS) {
+            let color = if editor.hex == Some(hex) { SELECTED_COLOR } else { MUTED_COLOR };
+            rows.push(commands.spawn((
+                label(format!("  {} ({}, {})", note.title, hex.x, hex.y), FONT_SIZE, color),
+                Interaction::default(),
+                NoteButton::Result(hex),
+            )).id());
+        }
+    }
+    commands.entity(panel.search).push_children(&rows);
+
+    commands.entity(panel.inspector).despawn_descendants();
+    let Some(hex) = editor.hex else { return; };
+    if !map.has_tile(hex) {
+        let row = commands.spawn(label(format!("Hex ({}, {}) has no tile", hex.x, hex.y), FONT_SIZE, MUTED_COLOR)).id();
+        commands.entity(panel.inspector).push_children(&[row]);
+        return;
+    }
+    let draft = &editor.draft;
+    let body = match (draft.body.is_empty(), editor.field == Some(NoteField::Body)) {
+        (_, true) => Text::from_section(format!("{}_", draft.body), TextStyle {
+            font_size: FONT_SIZE,
+            color: EDITING_COLOR,
+            ..default()
+        }),
+        (true, false) => Text::from_section("(no description)", TextStyle {
+            font_size: FONT_SIZE,
+            color: MUTED_COLOR,
+            ..default()
+        }),
+        (false, false) => markdown(&draft.body, Color::WHITE),
+    };
+    let rows = [
+        commands.spawn(label(format!("Hex ({}, {})", hex.x, hex.y), FONT_SIZE, MUTED_COLOR)).id(),
+        commands.spawn((
+            label(format!("{}{}", draft.title, cursor(NoteField::Title)), HEADING_SIZE, color(NoteField::Title)),
+            Interaction::default(),
+            NoteButton::Field(NoteField::Title),
+        )).id(),
+        commands.spawn((
+            label(format!("Tags: {}{}", draft.tags.join(", "), cursor(NoteField::Tags)), FONT_SIZE, color(NoteField::Tags)),
+            Interaction::default(),
+            NoteButton::Field(NoteField::Tags),
+        )).id(),
+        commands.spawn((
+            label(format!("Visible to: {}", draft.visibility.name()), FONT_SIZE, Color::WHITE),
+            Interaction::default(),
+            NoteButton::Visibility,
+        )).id(),
+        commands.spawn((
+            TextBundle::from_sections(body.sections),
+            Interaction::default(),
+            NoteButton::Field(NoteField::Body),
+        )).id(),
+        commands.spawn((
+            label("Delete note", FONT_SIZE, MUTED_COLOR),
+            Interaction::default(),
+            NoteButton::Delete,
+        )).id(),
+    ];
+    commands.entity(panel.inspector).push_children(&rows);
+}
+
+pub fn highlight_selected_hex(
+    selected: Res<SelectedHex>,
+    layout: Res<HexLayoutResource>,
+    mut gizmos: Gizmos,
+) {
+    let Some(hex) = **selected else { return; };
+    let corners = layout.hex_corners(hex);
+    gizmos.linestrip_2d(corners.into_iter().chain([corners[0]]), SELECTED_COLOR);
+}
@@ -0,0 +1,210 @@
+use bevy::prelude::*;
+use hexx::Hex;
+use crate::app::resources::{Brush, BrushButton, BrushPanel, InitiativeInput, SeasonButton, SelectedTool, Stroke};
+use crate::app::tool::Tool;
+use crate::map::brush::{BrushShape, MAX_BRUSH_RADIUS};
+use crate::map::query::MapQuery;
+use crate::map::resources::Map;
+use crate::map::season::Season;
+use crate::model::id::Id;
+use crate::view::query::UIQuery;
+use crate::view::resources::HexLayoutResource;
+use crate::view::ui::get_hovered_hex;
+
+const FONT_SIZE: f32 = 32.;
+const PREVIEW_COLOR: Color = Color::srgba(1., 1., 1., 0.6);
+
+/// Spawns the brush settings, returning the node to put in the admin bar.
+pub fn spawn_brush_panel(commands: &mut Commands) -> Entity {
+    let label = |commands: &mut Commands, text: &str, button: BrushButton| commands.spawn((
+        TextBundle::from_section(text, TextStyle {
+            font_size: FONT_SIZE,
+            ..default()
+        }).with_style(Style {
+            margin: UiRect::horizontal(Val::Px(8.)),
+            ..default()
+        }),
+        Interaction::default(),
+        button,
+    )).id();
+    let shape = label(commands, BrushShape::default().name(), BrushButton::Shape);
+    let smaller = label(commands, "-", BrushButton::Radius(-1));
+    let radius = label(commands, "0", BrushButton::Radius(0));
+    let bigger = label(commands, "+", BrushButton::Radius(1));
+    let season = commands.spawn((
+        TextBundle::from_section(Season::default().color(), TextStyle {
+            font_size: FONT_SIZE,
+            ..default()
+        }).with_style(Style {
+            margin: UiRect::left(Val::Px(24.)),
+            ..default()
+        }),
+        Interaction::default(),
+        SeasonButton,
+    )).id();
+    commands.insert_resource(BrushPanel { shape, radius, season });
+    commands.init_resource::<Brush>();
+    commands.spawn(NodeBundle {
+        style: Style {
+            display: Display::Flex,
+            flex_direction: FlexDirection::Row,
+            align_items: AlignItems::Center,
+            padding: UiRect::horizontal(Val::Px(8.)),
+            ..default()
+        },
+        ..default()
+    }).push_children(&[shape, smaller, radius, bigger, season]).id()
+}
+
+fn resize(brush: &mut Brush, change: i32) {
+    brush.radius = brush.radius.saturating_add_signed(change).min(MAX_BRUSH_RADIUS);
+}
+
+pub fn brush_buttons(
+    buttons: Query<(Ref<Interaction>, &BrushButton)>,
+    mut brush: ResMut<Brush>,
+) {
+    for (interaction, button) in &buttons {
+        if !interaction.is_changed() || *interaction != Interaction::Pressed { continue; }
+        match button {
+            BrushButton::Shape => brush.shape = brush.shape.next(),
+            BrushButton::Radius(change) => resize(&mut brush, *change),
+        }
+    }
+}
+
+pub fn brush_shortcuts(
+    keys: Res<ButtonInput<KeyCode>>,
+    input: Res<InitiativeInput>,
+    mut brush: ResMut<Brush>,
+) {
+    if input.is_editing() { return; }
+    if keys.just_pressed(KeyCode::KeyB) {
+        brush.shape = brush.shape.next();
+    }
+    if keys.just_pressed(KeyCode::BracketLeft) {
+        resize(&mut brush, -1);
+    }
+    if keys.just_pressed(KeyCode::BracketRight) {
+        resize(&mut brush, 1);
+    }
+}
+
+pub fn render_brush_panel(
+    brush: Res<Brush>,
+    panel: Res<BrushPanel>,
+    mut texts: Query<&mut Text>,
+) {
+    if !brush.is_changed() { return; }
+    if let Ok(mut text) = texts.get_mut(panel.shape) {
+        text.sections[0].value = brush.shape.name().to_string();
+    }
+    if let Ok(mut text) = texts.get_mut(panel.radius) {
+        text.sections[0].value = brush.radius.to_string();
+    }
+}
+
+/// Flips the whole map between the seasons, from the admin bar or with P.
+pub fn toggle_season(
+    buttons: Query<Ref<Interaction>, With<SeasonButton>>,
+    keys: Res<ButtonInput<KeyCode>>,
+    input: Res<InitiativeInput>,
+    mut map: MapQuery,
+) {
+    let clicked = buttons.iter().any(|interaction| interaction.is_changed() && *interaction == Interaction::Pressed);
+    let pressed = !input.is_editing() && keys.just_pressed(KeyCode::KeyP);
+    if !clicked && !pressed { return; }
+    let season = map.map.season().next();
+    map.set_season(season);
+}
+
+pub fn render_season(
+    map: Res<Map>,
+    panel: Res<BrushPanel>,
+    mut texts: Query<&mut Text>,
+) {
+    if !map.is_changed() { return; }
+    let Ok(mut text) = texts.get_mut(panel.season) else { return; };
+    if text.sections[0].value != map.season().color() {
+        text.sections[0].value = map.season().color().to_string();
+    }
+}
+
+/// `id` is either a single texture or a family to pick variants from.
+fn paint(map: &mut MapQuery, hexes: impl IntoIterator<Item = Hex>, id: &Id) {
+    let id = &map.map.in_season(&map.texture_tree, id.clone());
+    for hex in hexes {
+        //Repainting a tile from its own family would only reshuffle it
+        if map.map.background(hex).is_some_and(|background| background.starts_with(id)) { continue; }
+        map.place_tile(hex, id.clone());
+    }
+}
+
+pub fn paint_tiles(
+    mut ui: UIQuery,
+    mouse_button: Res<ButtonInput<MouseButton>>,
+    selected: Res<SelectedTool>,
+    interaction_query: Query<&Interaction, With<Node>>,
+    mut brush: ResMut<Brush>,
+    mut stroke: ResMut<Stroke>,
+    mut map: MapQuery,
+) {
+    let Some(Tool::Background(id)) = &**selected else { return; };
+    let hovered = get_hovered_hex(&mut ui, true);
+    if mouse_button.just_released(MouseButton::Left) {
+        stroke.0 = None;
+        let Some(anchor) = brush.anchor.take() else { return; };
+        let Some(hex) = hovered.filter(|_| brush.shape.is_dragged()) else { return; };
+        let hexes = brush.shape.hexes(anchor, hex, brush.radius);
+        paint(&mut map, hexes, id);
+        return;
+    }
+    if !mouse_button.pressed(MouseButton::Left) { return; }
+    //Painting has to start on the map, not on the admin bar
+    let starting = mouse_button.just_pressed(MouseButton::Left);
+    if starting && interaction_query.iter().any(|interaction| *interaction != Interaction::None) { return; }
+    if !starting && brush.anchor.is_none() { return; }
+    let Some(hex) = hovered else { return; };
+    if starting {
+        brush.anchor = Some(hex);
+    }
+    match brush.shape {
+        BrushShape::Brush => {
+            //Fast strokes skip hexes, fill them in so the stroke has no gaps
+            let from = stroke.0.unwrap_or(hex);
+            if !starting && from == hex { return; }
+            let hexes: Vec<Hex> = from.line_to(hex)
+                .flat_map(|center| BrushShape::Brush.hexes(center, center, brush.radius))
+                .collect();
+            paint(&mut map, hexes, id);
+            stroke.0 = Some(hex);
+        }
+        BrushShape::Fill if starting => {
+            let hexes = map.map.flood_fill(hex);
+            info!("Filling {} tiles.", hexes.len());
+            paint(&mut map, hexes, id);
+        }
+        _ => {}
+    }
+}
+
+/// Outlines the hexes the background tool is about to paint.
+pub fn preview_brush(
+    mut ui: UIQuery,
+    selected: Res<SelectedTool>,
+    brush: Res<Brush>,
+    layout: Res<HexLayoutResource>,
+    mut gizmos: Gizmos,
+) {
+    let Some(Tool::Background(_)) = &**selected else { return; };
+    let Some(hex) = get_hovered_hex(&mut ui, true) else { return; };
+    let hexes = match brush.anchor {
+        Some(anchor) if brush.shape.is_dragged() => brush.shape.hexes(anchor, hex, brush.radius),
+        _ if brush.shape.is_dragged() => vec![hex],
+        _ => brush.shape.hexes(hex, hex, brush.radius),
+    };
+    for hex in hexes {
+        let corners = layout.hex_corners(hex);
+        gizmos.linestrip_2d(corners.into_iter().chain([corners[0]]), PREVIEW_COLOR);
+    }
+}
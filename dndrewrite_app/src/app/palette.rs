@@ -0,0 +1,249 @@
+use bevy::prelude::*;
+use crate::app::resources::{InitiativeInput, Palette, PaletteButton, PaletteRow, SelectedTool};
+use crate::app::tool::Tool;
+use crate::map::tile::OverlayKind;
+use crate::model::id::Id;
+use crate::model::resources::TextureTreeResource;
+use crate::model::settings::{SETTINGS_FILE, Settings};
+use crate::model::texture_tree::{TextureLeaf, TextureNode};
+
+const FONT_SIZE: f32 = 20.;
+const MUTED_COLOR: Color = Color::srgb(0.6, 0.6, 0.6);
+const FAVORITE_COLOR: Color = Color::srgb(1., 0.85, 0.3);
+const SELECTED_COLOR: Color = Color::srgb(0.5, 0.8, 1.);
+/// One slot per number key, 1 to 9 and then 0.
+const SLOTS: usize = 10;
+const RECENT_LIMIT: usize = SLOTS;
+
+/// Spawns the palette row, returning the node to put above the admin bar.
+pub fn spawn_palette(commands: &mut Commands) -> Entity {
+    let settings = Settings::read().unwrap_or_else(|err| {
+        warn!("Starting with an empty palette, {} can't be read: {}", SETTINGS_FILE, err);
+        Settings::default()
+    });
+    commands.insert_resource(Palette {
+        favorites: settings.favorites,
+        recent: settings.recent,
+    });
+    let star = commands.spawn((
+        TextBundle::from_section("Star", TextStyle {
+            font_size: FONT_SIZE,
+            color: MUTED_COLOR,
+            ..default()
+        }).with_style(Style {
+            margin: UiRect::horizontal(Val::Px(8.)),
+            ..default()
+        }),
+        Interaction::default(),
+        PaletteButton::Star,
+    )).id();
+    let slots = commands.spawn(NodeBundle {
+        style: Style {
+            display: Display::Flex,
+            flex_direction: FlexDirection::Row,
+            column_gap: Val::Px(4.),
+            ..default()
+        },
+        ..default()
+    }).id();
+    commands.insert_resource(PaletteRow { star, slots });
+    commands.spawn((NodeBundle {
+        style: Style {
+            display: Display::Flex,
+            flex_direction: FlexDirection::Row,
+            align_items: AlignItems::Center,
+            padding: UiRect::all(Val::Px(4.)),
+            ..default()
+        },
+        background_color: BackgroundColor(Color::srgba(0., 0., 0., 0.6)),
+        ..default()
+    }, Interaction::default())).push_children(&[star, slots]).id()
+}
+
+impl Palette {
+    /// Favorites first, then the recent textures that aren't starred.
+    fn ids(&self) -> impl Iterator<Item = &Id> {
+        self.favorites.iter()
+            .chain(self.recent.iter().filter(|id| !self.favorites.contains(id)))
+    }
+
+    /// The slots the number keys select, skipping textures that are no longer loaded.
+    fn slots(&self, tree: &TextureNode) -> Vec<(Id, Tool, TextureLeaf)> {
+        self.ids()
+            .filter_map(|id| Some((id.clone(), tool(tree, id)?, thumbnail(tree, id)?)))
+            .take(SLOTS)
+            .collect()
+    }
+
+    fn toggle_favorite(&mut self, id: &Id) {
+        if let Some(index) = self.favorites.iter().position(|favorite| favorite == id) {
+            self.favorites.remove(index);
+        } else {
+            self.favorites.push(id.clone());
+        }
+    }
+
+    fn use_texture(&mut self, id: &Id) {
+        self.recent.retain(|recent| recent != id);
+        self.recent.insert(0, id.clone());
+        self.recent.truncate(RECENT_LIMIT);
+    }
+}
+
+/// Leaves select themselves, families paint random variants or draw linear overlays.
+fn tool(tree: &TextureNode, id: &Id) -> Option<Tool> {
+    let node = tree.get(id)?;
+    if node.leaf().is_some() {
+        return Some(Tool::from_texture(id.clone()));
+    }
+    match OverlayKind::from_id(id) {
+        Some(kind) if kind.is_linear() => Some(Tool::Linear(kind, id.clone())),
+        Some(_) => None,
+        None => Some(Tool::Background(id.clone())),
+    }
+}
+
+fn thumbnail(tree: &TextureNode, id: &Id) -> Option<TextureLeaf> {
+    let node = tree.get(id)?;
+    node.get(&node.first_leaf_id()?)?.leaf()
+}
+
+fn slot_key(key: KeyCode) -> Option<usize> {
+    Some(match key {
+        KeyCode::Digit1 | KeyCode::Numpad1 => 0,
+        KeyCode::Digit2 | KeyCode::Numpad2 => 1,
+        KeyCode::Digit3 | KeyCode::Numpad3 => 2,
+        KeyCode::Digit4 | KeyCode::Numpad4 => 3,
+        KeyCode::Digit5 | KeyCode::Numpad5 => 4,
+        KeyCode::Digit6 | KeyCode::Numpad6 => 5,
+        KeyCode::Digit7 | KeyCode::Numpad7 => 6,
+        KeyCode::Digit8 | KeyCode::Numpad8 => 7,
+        KeyCode::Digit9 | KeyCode::Numpad9 => 8,
+        KeyCode::Digit0 | KeyCode::Numpad0 => 9,
+        _ => return None,
+    })
+}
+
+fn select(selected: &mut SelectedTool, tool: Tool) {
+    info!("Selected tool {:?}.", tool);
+    selected.0 = Some(tool);
+}
+
+pub fn palette_buttons(
+    buttons: Query<(Ref<Interaction>, &PaletteButton)>,
+    tree: Res<TextureTreeResource>,
+    mut palette: ResMut<Palette>,
+    mut selected: ResMut<SelectedTool>,
+) {
+    for (interaction, button) in &buttons {
+        if !interaction.is_changed() || *interaction != Interaction::Pressed { continue; }
+        match button {
+            PaletteButton::Star => {
+                let Some(id) = selected.0.as_ref().and_then(Tool::texture) else { continue; };
+                palette.toggle_favorite(id);
+            }
+            PaletteButton::Slot(id) => {
+                let Some(tool) = tool(&tree.0, id) else { continue; };
+                select(&mut selected, tool);
+            }
+        }
+    }
+}
+
+/// Number keys pick palette slots, unless they're typing an initiative.
+pub fn palette_shortcuts(
+    keys: Res<ButtonInput<KeyCode>>,
+    input: Res<InitiativeInput>,
+    tree: Res<TextureTreeResource>,
+    palette: Res<Palette>,
+    mut selected: ResMut<SelectedTool>,
+) {
+    if input.is_editing() { return; }
+    let Some(index) = keys.get_just_pressed().find_map(|key| slot_key(*key)) else { return; };
+    let Some((_, tool, _)) = palette.slots(&tree.0).into_iter().nth(index) else { return; };
+    select(&mut selected, tool);
+}
+
+/// Every texture tool that gets selected moves to the front of the recent ones.
+pub fn remember_selection(
+    selected: Res<SelectedTool>,
+    mut palette: ResMut<Palette>,
+) {
+    if !selected.is_changed() { return; }
+    let Some(id) = selected.0.as_ref().and_then(Tool::texture) else { return; };
+    if palette.recent.first() != Some(id) {
+        palette.use_texture(id);
+    }
+}
+
+pub fn save_palette(palette: Res<Palette>) {
+    if !palette.is_changed() || palette.is_added() { return; }
+    //Read again so the rest of the file is kept as it is
+    let mut settings = match Settings::read() {
+        Ok(settings) => settings,
+        Err(err) => {
+            warn!("Not saving the palette, {} can't be read: {}", SETTINGS_FILE, err);
+            return;
+        }
+    };
+    settings.favorites.clone_from(&palette.favorites);
+    settings.recent.clone_from(&palette.recent);
+    if let Err(err) = settings.write() {
+        warn!("Couldn't save the palette to {}: {}", SETTINGS_FILE, err);
+    }
+}
+
+pub fn render_palette(
+    row: Res<PaletteRow>,
+    palette: Res<Palette>,
+    selected: Res<SelectedTool>,
+    tree: Res<TextureTreeResource>,
+    mut texts: Query<&mut Text>,
+    mut commands: Commands,
+) {
+    if !palette.is_changed() && !selected.is_changed() && !tree.is_changed() { return; }
+    let current = selected.0.as_ref().and_then(Tool::texture);
+    if let Ok(mut text) = texts.get_mut(row.star) {
+        let section = &mut text.sections[0];
+        (section.value, section.style.color) = match current {
+            Some(id) if palette.favorites.contains(id) => ("Unstar".to_string(), FAVORITE_COLOR),
+            Some(_) => ("Star".to_string(), Color::WHITE),
+            None => ("Star".to_string(), MUTED_COLOR),
+        };
+    }
+    commands.entity(row.slots).despawn_descendants();
+    let slots: Vec<Entity> = palette.slots(&tree.0).into_iter()
+        .enumerate()
+        .map(|(index, (id, _, leaf))| {
+            let number_color = if palette.favorites.contains(&id) { FAVORITE_COLOR } else { Color::WHITE };
+            let border_color = if current == Some(&id) { SELECTED_COLOR } else { Color::NONE };
+            let number = commands.spawn(TextBundle::from_section(((index + 1) % SLOTS).to_string(), TextStyle {
+                font_size: FONT_SIZE,
+                color: number_color,
+                ..default()
+            }).with_style(Style {
+                position_type: PositionType::Absolute,
+                left: Val::Px(2.),
+                top: Val::Px(0.),
+                ..default()
+            })).id();
+            commands.spawn((
+                ImageBundle {
+                    style: Style {
+                        width: Val::Vh(6.),
+                        height: Val::Vh(6.),
+                        border: UiRect::all(Val::Px(2.)),
+                        ..default()
+                    },
+                    image: UiImage::new(leaf.image),
+                    ..default()
+                },
+                BorderColor(border_color),
+                leaf.atlas,
+                Interaction::default(),
+                PaletteButton::Slot(id),
+            )).push_children(&[number]).id()
+        })
+        .collect();
+    commands.entity(row.slots).push_children(&slots);
+}
@@ -2,6 +2,8 @@ use std::sync::Arc;
 use bevy::prelude::*;
 use bevy::utils::HashMap;
 use crate::app::admin_button::AdminButton;
+use crate::map::brush::BrushShape;
+use crate::map::note::Note;
 use super::*;
 use crate::model::*;
 
@@ -14,12 +16,211 @@ pub struct AdminMenus(pub(super) HashMap<id::Id, Arc<admin_menu::AdminMenu>>);
 #[derive(Resource, Debug, Default, Deref, DerefMut)]
 pub struct AdminMenuStack(pub(super) id::Id);
 
+#[derive(Resource, Debug, Default, Deref, DerefMut)]
+pub struct SelectedTool(pub(super) Option<tool::Tool>);
+
+/// The last hex of the stroke being drawn, while the mouse is held.
+#[derive(Resource, Debug, Default, Deref, DerefMut)]
+pub struct Stroke(pub(super) Option<hexx::Hex>);
+
+/// The token being dragged with the move tool and its planned path.
+#[derive(Resource, Debug, Default)]
+pub struct TokenDrag {
+    pub(super) combatant: Option<Entity>,
+    pub(super) path: Vec<hexx::Hex>,
+    /// Length of the path in feet.
+    pub(super) cost: u32,
+    pub(super) label: Option<Entity>,
+}
+
+/// How the background tool paints.
+#[derive(Resource, Debug, Default)]
+pub struct Brush {
+    pub(super) shape: BrushShape,
+    pub(super) radius: u32,
+    /// Where the shape being dragged out started, while the mouse is held.
+    pub(super) anchor: Option<hexx::Hex>,
+}
+
+/// The labels showing the brush settings in the admin bar.
+#[derive(Resource, Debug)]
+pub struct BrushPanel {
+    pub(super) shape: Entity,
+    pub(super) radius: Entity,
+    pub(super) season: Entity,
+}
+
+#[derive(Component, Debug, Clone, Copy)]
+pub enum BrushButton {
+    Shape,
+    Radius(i32),
+}
+
+#[derive(Component, Debug)]
+pub struct SeasonButton;
+
+/// The hex whose note is shown in the inspector.
+#[derive(Resource, Debug, Default, Deref, DerefMut)]
+pub struct SelectedHex(pub(super) Option<hexx::Hex>);
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum NoteField {
+    Title,
+    Tags,
+    Body,
+    Search,
+}
+
+/// The note being edited and the text field keys are typed into.
+#[derive(Resource, Debug, Default)]
+pub struct NoteEditor {
+    pub(super) hex: Option<hexx::Hex>,
+    pub(super) draft: Note,
+    pub(super) field: Option<NoteField>,
+    pub(super) search: String,
+}
+
+impl NoteEditor {
+    pub fn is_editing(&self) -> bool {
+        self.field.is_some()
+    }
+}
+
+/// The nodes the notes panel is rendered into.
+#[derive(Resource, Debug)]
+pub struct NotesPanel {
+    pub(super) search: Entity,
+    pub(super) inspector: Entity,
+}
+
+#[derive(Component, Debug, Clone, Copy)]
+pub enum NoteButton {
+    Field(NoteField),
+    Visibility,
+    Delete,
+    Result(hexx::Hex),
+}
+
+/// The two ends of the ruler, while it is dragged and after.
+#[derive(Resource, Debug, Default)]
+pub struct Ruler {
+    pub(super) start: Option<hexx::Hex>,
+    pub(super) end: Option<hexx::Hex>,
+}
+
+/// The texts of the status bar at the top of the admin window.
+#[derive(Resource, Debug)]
+pub struct StatusBar {
+    pub(super) coordinates: Entity,
+    pub(super) scale: Entity,
+}
+
+#[derive(Component, Debug)]
+pub struct ScaleButton;
+
+/// The asset problems in the corner of the admin window, folded into a
+/// header until it is clicked.
+#[derive(Resource, Debug)]
+pub struct AssetReportPanel {
+    pub(super) panel: Entity,
+    pub(super) header: Entity,
+    pub(super) list: Entity,
+    pub(super) expanded: bool,
+}
+
+#[derive(Component, Debug)]
+pub struct AssetReportButton;
+
+/// Offset coordinates the minimap covers, odd rows take the right half of
+/// their two pixels wide cells.
+#[derive(Debug, Copy, Clone, Default, PartialEq)]
+pub struct MinimapBounds {
+    pub(super) left: i32,
+    pub(super) bottom: i32,
+    pub(super) columns: u32,
+    pub(super) rows: u32,
+}
+
+#[derive(Resource, Debug)]
+pub struct Minimap {
+    pub(super) image: Handle<Image>,
+    pub(super) node: Entity,
+    pub(super) admin_view: Entity,
+    pub(super) user_view: Entity,
+    pub(super) bounds: MinimapBounds,
+}
+
 #[derive(Resource, Default)]
 pub struct AppLoaded;
 
 #[derive(Component, DerefMut, Deref)]
 pub struct AdminButtonMarker(pub Arc<AdminButton>);
 
+/// The box above the admin bar describing the hovered button.
+#[derive(Resource, Debug)]
+pub struct AdminTooltip {
+    pub(super) node: Entity,
+    pub(super) text: Entity,
+}
+
+/// The query typed into the admin bar's texture search.
+#[derive(Resource, Debug)]
+pub struct TextureSearch {
+    pub(super) field: Entity,
+    pub(super) query: String,
+    pub(super) focused: bool,
+}
+
+impl TextureSearch {
+    pub fn is_editing(&self) -> bool {
+        self.focused
+    }
+}
+
+#[derive(Component, Debug)]
+pub struct TextureSearchField;
+
+/// Starred and recently selected textures, kept in the settings file.
+#[derive(Resource, Debug, Default)]
+pub struct Palette {
+    pub(super) favorites: Vec<id::Id>,
+    pub(super) recent: Vec<id::Id>,
+}
+
+/// The row above the admin bar the palette is rendered into.
+#[derive(Resource, Debug)]
+pub struct PaletteRow {
+    pub(super) star: Entity,
+    pub(super) slots: Entity,
+}
+
+#[derive(Component, Debug, Clone)]
+pub enum PaletteButton {
+    /// Stars or unstars the selected texture.
+    Star,
+    Slot(id::Id),
+}
+
+#[derive(Component, Debug, Deref)]
+pub struct InitiativeRow(pub Entity);
+
+/// The list node the initiative order is rendered into.
+#[derive(Resource, Debug, Deref)]
+pub struct InitiativePanel(pub(super) Entity);
+
+/// Initiative typed in for the combatant whose row was clicked.
+#[derive(Resource, Debug, Default)]
+pub struct InitiativeInput {
+    pub(super) combatant: Option<Entity>,
+    pub(super) buffer: String,
+}
+
+impl InitiativeInput {
+    pub fn is_editing(&self) -> bool {
+        self.combatant.is_some()
+    }
+}
+
 #[derive(Resource, Debug)]
 pub struct UITracker {
     admin_bar: Entity,
@@ -0,0 +1,125 @@
+use bevy::prelude::*;
+use crate::app::resources::{Ruler, ScaleButton, SelectedTool, StatusBar};
+use crate::app::tool::Tool;
+use crate::map::brush::to_offset;
+use crate::map::resources::Map;
+use crate::view::query::UIQuery;
+use crate::view::resources::HexLayoutResource;
+use crate::view::ui::get_hovered_hex;
+
+const FONT_SIZE: f32 = 20.;
+const RULER_COLOR: Color = Color::srgb(1., 0.5, 0.1);
+
+pub fn spawn_status_bar(commands: &mut Commands, admin_camera: Entity) {
+    let text = |text: &str| TextBundle::from_section(text, TextStyle {
+        font_size: FONT_SIZE,
+        ..default()
+    }).with_style(Style {
+        margin: UiRect::horizontal(Val::Px(12.)),
+        ..default()
+    });
+    let coordinates = commands.spawn(text("")).id();
+    let scale = commands.spawn((text(""), Interaction::default(), ScaleButton)).id();
+    commands.spawn((NodeBundle {
+        style: Style {
+            position_type: PositionType::Absolute,
+            top: Val::Px(0.),
+            width: Val::Percent(100.),
+            justify_content: JustifyContent::Center,
+            ..default()
+        },
+        ..default()
+    }, TargetCamera(admin_camera))).with_children(|parent| {
+        parent.spawn((NodeBundle {
+            style: Style {
+                padding: UiRect::all(Val::Px(4.)),
+                ..default()
+            },
+            background_color: BackgroundColor(Color::srgba(0., 0., 0., 0.6)),
+            ..default()
+        }, Interaction::default())).push_children(&[coordinates, scale]);
+    });
+    commands.insert_resource(StatusBar { coordinates, scale });
+    commands.init_resource::<Ruler>();
+}
+
+pub fn measure(
+    mut ui: UIQuery,
+    mouse_button: Res<ButtonInput<MouseButton>>,
+    selected: Res<SelectedTool>,
+    interaction_query: Query<&Interaction, With<Node>>,
+    mut ruler: ResMut<Ruler>,
+) {
+    if !matches!(**selected, Some(Tool::Ruler)) {
+        if ruler.start.is_some() {
+            *ruler = Ruler::default();
+        }
+        return;
+    }
+    if !mouse_button.pressed(MouseButton::Left) { return; }
+    let starting = mouse_button.just_pressed(MouseButton::Left);
+    if starting && interaction_query.iter().any(|interaction| *interaction != Interaction::None) { return; }
+    let Some(hex) = get_hovered_hex(&mut ui, true) else { return; };
+    if starting {
+        ruler.start = Some(hex);
+    }
+    if ruler.end != Some(hex) {
+        ruler.end = Some(hex);
+    }
+}
+
+pub fn draw_ruler(
+    ruler: Res<Ruler>,
+    layout: Res<HexLayoutResource>,
+    mut gizmos: Gizmos,
+) {
+    let (Some(start), Some(end)) = (ruler.start, ruler.end) else { return; };
+    let (from, to) = (layout.hex_to_world_pos(start), layout.hex_to_world_pos(end));
+    gizmos.line_2d(from, to, RULER_COLOR);
+    gizmos.circle_2d(from, 16., RULER_COLOR);
+    gizmos.circle_2d(to, 16., RULER_COLOR);
+}
+
+/// Steps through the scale presets when the scale is clicked.
+pub fn change_scale(
+    buttons: Query<Ref<Interaction>, With<ScaleButton>>,
+    mut map: ResMut<Map>,
+) {
+    for interaction in &buttons {
+        if interaction.is_changed() && *interaction == Interaction::Pressed {
+            let scale = map.scale().next();
+            map.set_scale(scale);
+        }
+    }
+}
+
+pub fn update_status_bar(
+    mut ui: UIQuery,
+    map: Res<Map>,
+    ruler: Res<Ruler>,
+    status_bar: Res<StatusBar>,
+    mut texts: Query<&mut Text>,
+) {
+    let mut status = match get_hovered_hex(&mut ui, true) {
+        Some(hex) => {
+            let (column, row) = to_offset(hex);
+            format!("Axial ({}, {})   Offset ({}, {})", hex.x, hex.y, column, row)
+        }
+        None => String::new(),
+    };
+    if let (Some(start), Some(end)) = (ruler.start, ruler.end) {
+        let hexes = start.unsigned_distance_to(end);
+        status += &format!("   Ruler: {} hexes = {}", hexes, map.scale().describe(hexes));
+    }
+    if let Ok(mut text) = texts.get_mut(status_bar.coordinates) {
+        if text.sections[0].value != status {
+            text.sections[0].value = status;
+        }
+    }
+    let scale = format!("1 hex = {}", map.scale().describe(1));
+    if let Ok(mut text) = texts.get_mut(status_bar.scale) {
+        if text.sections[0].value != scale {
+            text.sections[0].value = scale;
+        }
+    }
+}
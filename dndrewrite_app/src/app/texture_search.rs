@@ -0,0 +1,238 @@
+use std::sync::Arc;
+use bevy::input::keyboard::{Key, KeyboardInput};
+use bevy::prelude::*;
+use crate::app::admin_button::AdminButton;
+use crate::app::admin_menu::AdminMenu;
+use crate::app::notes_panel;
+use crate::app::resources::{CurrentAdminMenu, InitiativeInput, NoteEditor, SelectedTool, TextureSearch, TextureSearchField};
+use crate::app::tool::Tool;
+use crate::map::query::MapQuery;
+use crate::model::id::Id;
+use crate::model::resources::TextureTreeResource;
+use crate::model::texture_tree::TextureNode;
+
+const EDITING_COLOR: Color = Color::srgb(0.5, 0.8, 1.);
+const MUTED_COLOR: Color = Color::srgb(0.6, 0.6, 0.6);
+const FONT_SIZE: f32 = 24.;
+/// More buttons than this don't fit the bar anyway.
+const MAX_RESULTS: usize = 60;
+
+pub fn spawn_texture_search(commands: &mut Commands) -> Entity {
+    let field = commands.spawn((
+        TextBundle::from_section("", TextStyle {
+            font_size: FONT_SIZE,
+            color: MUTED_COLOR,
+            ..default()
+        }).with_style(Style {
+            min_width: Val::Px(160.),
+            margin: UiRect::horizontal(Val::Px(16.)),
+            ..default()
+        }),
+        Interaction::default(),
+        TextureSearchField,
+    )).id();
+    commands.insert_resource(TextureSearch {
+        field,
+        query: String::new(),
+        focused: false,
+    });
+    field
+}
+
+/// How well the query matches the text, `None` if some of it doesn't.
+/// Every word of the query has to be found, whole or as a subsequence.
+fn fuzzy_score(query: &str, text: &str) -> Option<i32> {
+    query.split_whitespace()
+        .map(|word| word_score(word, text))
+        .sum()
+}
+
+fn word_score(word: &str, text: &str) -> Option<i32> {
+    let is_start = |index: usize| text[..index].ends_with(['_', '/', ' ', '.']) || index == 0;
+    if let Some(index) = text.find(word) {
+        let start = if is_start(index) { 20 } else { 0 };
+        return Some(50 + 4 * word.len() as i32 + start);
+    }
+    //Letters in order, rewarding runs and letters that begin a word
+    let mut score = 0;
+    let mut last = None;
+    let mut chars = text.char_indices();
+    for letter in word.chars() {
+        let (index, _) = chars.find(|(_, c)| *c == letter)?;
+        score += 1;
+        if last.is_some_and(|last| last + letter.len_utf8() == index) {
+            score += 3;
+        }
+        if is_start(index) {
+            score += 2;
+        }
+        last = Some(index);
+    }
+    Some(score)
+}
+
+/// Leaves matching the query by path or display name, best first.
+fn find_textures(tree: &TextureNode, query: &str) -> Vec<Id> {
+    let query = query.to_lowercase();
+    let mut results: Vec<(i32, String, Id)> = tree.leaf_ids().into_iter()
+        .filter_map(|id| {
+            let path = id.to_string().to_lowercase();
+            let name = tree.get(&id)
+                .and_then(TextureNode::meta)
+                .and_then(|meta| meta.name.as_ref())
+                .map(|name| name.to_lowercase());
+            let score = fuzzy_score(&query, &path)
+                .max(name.and_then(|name| fuzzy_score(&query, &name)))?;
+            Some((score, path, id))
+        })
+        .collect();
+    //Shorter paths first among equals, they're the closer match
+    results.sort_by(|(a, a_path, _), (b, b_path, _)| b.cmp(a).then(a_path.len().cmp(&b_path.len())));
+    results.into_iter().take(MAX_RESULTS).map(|(_, _, id)| id).collect()
+}
+
+/// Buttons selecting the found leaves directly.
+fn results_menu(tree: &TextureNode, query: &str) -> AdminMenu {
+    let buttons = find_textures(tree, query).into_iter()
+        .filter_map(|id| {
+            let node = tree.get(&id)?;
+            let name = id.last()?.clone();
+            let meta = node.meta().cloned().unwrap_or_default();
+            let tooltip = format!("{}\n{}", meta.tooltip(&name), id);
+            Some(Arc::new(AdminButton {
+                texture: node.leaf()?,
+                tooltip,
+                name,
+                on_click: Box::new(move |_| id.clone()),
+                on_hover: Box::new(|mut window| window.cursor.icon = CursorIcon::Pointer),
+            }))
+        })
+        .collect();
+    AdminMenu(buttons)
+}
+
+/// Clicking the field gives it the keyboard, clicking anything else takes it away.
+pub fn focus_texture_search(
+    fields: Query<Ref<Interaction>, With<TextureSearchField>>,
+    mouse_button: Res<ButtonInput<MouseButton>>,
+    mut search: ResMut<TextureSearch>,
+    mut editor: ResMut<NoteEditor>,
+    mut input: ResMut<InitiativeInput>,
+    mut map: MapQuery,
+) {
+    //Only one field types at a time
+    let other_field = (editor.is_changed() && editor.is_editing()) || (input.is_changed() && input.is_editing());
+    if search.focused && other_field {
+        search.focused = false;
+    }
+    let Ok(interaction) = fields.get(search.field) else { return; };
+    if interaction.is_changed() && *interaction == Interaction::Pressed {
+        notes_panel::stop_editing(&mut editor, &mut map);
+        if input.is_editing() {
+            *input = InitiativeInput::default();
+        }
+        search.focused = true;
+    } else if search.focused && mouse_button.just_pressed(MouseButton::Left) && *interaction == Interaction::None {
+        search.focused = false;
+    }
+}
+
+pub fn type_texture_search(
+    mut keyboard: EventReader<KeyboardInput>,
+    keys: Res<ButtonInput<KeyCode>>,
+    tree: Res<TextureTreeResource>,
+    mut search: ResMut<TextureSearch>,
+    mut selected: ResMut<SelectedTool>,
+) {
+    if !search.focused {
+        keyboard.clear();
+        return;
+    }
+    //Ctrl combinations are shortcuts, not text
+    let control = keys.any_pressed([KeyCode::ControlLeft, KeyCode::ControlRight]);
+    for event in keyboard.read() {
+        if !event.state.is_pressed() || control { continue; }
+        match &event.logical_key {
+            Key::Escape => {
+                search.query.clear();
+                search.focused = false;
+                return;
+            }
+            Key::Enter => {
+                let Some(id) = find_textures(&tree.0, &search.query).into_iter().next() else { continue; };
+                let tool = Tool::from_texture(id);
+                info!("Selected tool {:?}.", tool);
+                selected.0 = Some(tool);
+                search.focused = false;
+                return;
+            }
+            Key::Backspace => {
+                search.query.pop();
+            }
+            Key::Space => search.query.push(' '),
+            Key::Character(text) => search.query.push_str(text),
+            _ => {}
+        }
+    }
+}
+
+/// Shows the results in place of the open menu, and the menu again once the
+/// query is cleared.
+pub fn render_texture_search(
+    tree: Res<TextureTreeResource>,
+    current: Res<CurrentAdminMenu>,
+    mut search: ResMut<TextureSearch>,
+    mut texts: Query<&mut Text, With<TextureSearchField>>,
+    mut shown: Local<String>,
+    mut commands: Commands,
+) {
+    //Browsing the menus leaves the search
+    if current.is_changed() && !shown.is_empty() {
+        search.query.clear();
+        search.focused = false;
+        shown.clear();
+    }
+    if !search.is_changed() { return; }
+    if let Ok(mut text) = texts.get_mut(search.field) {
+        let section = &mut text.sections[0];
+        (section.value, section.style.color) = match (search.focused, search.query.is_empty()) {
+            (true, _) => (format!("Search: {}_", search.query), EDITING_COLOR),
+            (false, true) => ("Search textures".to_string(), MUTED_COLOR),
+            (false, false) => (format!("Search: {}", search.query), Color::WHITE),
+        };
+    }
+    if search.query == *shown { return; }
+    shown.clone_from(&search.query);
+    let menu = if search.query.is_empty() {
+        current.0.clone()
+    } else {
+        Arc::new(results_menu(&tree.0, &search.query))
+    };
+    commands.add(move |world: &mut World| menu.render(world));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DRACOLICH: &str = "overlay/figure/standard_full/adult_dracolich_blue_130";
+
+    #[test]
+    fn words_are_found_inside_paths() {
+        assert!(fuzzy_score("dracolich", DRACOLICH).is_some());
+        assert!(fuzzy_score("drclch", DRACOLICH).is_some());
+        assert_eq!(fuzzy_score("xyz", DRACOLICH), None);
+    }
+
+    #[test]
+    fn substrings_beat_subsequences() {
+        assert!(fuzzy_score("tree", "forest/tree_oak") > fuzzy_score("tree", "t_r_e_e"));
+        assert!(fuzzy_score("dense", "grassland/dense") > fuzzy_score("dense", "grassland/dunes_sparse"));
+    }
+
+    #[test]
+    fn every_word_has_to_match() {
+        assert!(fuzzy_score("blue dracolich", DRACOLICH).is_some());
+        assert_eq!(fuzzy_score("zombie dracolich", DRACOLICH), None);
+    }
+}
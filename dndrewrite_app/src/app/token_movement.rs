@@ -0,0 +1,148 @@
+use bevy::ecs::system::SystemParam;
+use bevy::prelude::*;
+use bevy::render::view::RenderLayers;
+use crate::app::resources::{SelectedTool, TokenDrag};
+use crate::app::tool::Tool;
+use crate::map::combatant::Combatant;
+use crate::map::movement::move_combatant;
+use crate::map::query::MapQuery;
+use crate::map::resources::Initiative;
+use crate::view::layers::ADMIN_LAYER;
+use crate::view::query::UIQuery;
+use crate::view::resources::HexLayoutResource;
+use crate::view::ui::get_hovered_hex;
+
+const REACHABLE_COLOR: Color = Color::srgb(0.3, 0.9, 0.3);
+const UNREACHABLE_COLOR: Color = Color::srgb(0.9, 0.2, 0.2);
+const LABEL_Z: f32 = 4.;
+
+pub fn move_tool_selected(selected: Res<SelectedTool>) -> bool {
+    matches!(**selected, Some(Tool::Move))
+}
+
+pub fn drag_token(
+    mut ui: UIQuery,
+    mouse_button: Res<ButtonInput<MouseButton>>,
+    interaction_query: Query<&Interaction, With<Node>>,
+    mut map: MapQuery,
+    initiative: Res<Initiative>,
+    mut drag: ResMut<TokenDrag>,
+    mut combatants: Query<(Entity, &mut Combatant, &mut Transform), Without<Camera>>,
+) {
+    if mouse_button.just_pressed(MouseButton::Left) {
+        drag.path.clear();
+        drag.combatant = None;
+        if interaction_query.iter().any(|interaction| *interaction != Interaction::None) { return; }
+        let Some(hex) = get_hovered_hex(&mut ui, true) else { return; };
+        drag.combatant = combatants.iter()
+            .find(|(_, combatant, _)| combatant.hex() == hex)
+            .map(|(entity, _, _)| entity);
+        return;
+    }
+    let Some(entity) = drag.combatant else { return; };
+    let Ok((_, mut combatant, mut transform)) = combatants.get_mut(entity) else {
+        drag.combatant = None;
+        return;
+    };
+    if mouse_button.just_released(MouseButton::Left) {
+        drag.combatant = None;
+        let Some(&target) = drag.path.last() else { return; };
+        let before = combatant.clone();
+        //Outside of an encounter nobody runs out of movement
+        if initiative.round() > 0 {
+            if drag.cost > combatant.remaining_movement() as u32 {
+                warn!("'{}' can't move {} ft, only {} ft left.", combatant.name(), drag.cost, combatant.remaining_movement());
+                return;
+            }
+            combatant.spend_movement(drag.cost as u16);
+        }
+        move_combatant(&map.layout, &mut combatant, &mut transform, target);
+        map.record_token(entity, before, combatant.clone());
+        return;
+    }
+    let Some(hex) = get_hovered_hex(&mut ui, true) else { return; };
+    if drag.path.last() == Some(&hex) { return; }
+    (drag.path, drag.cost) = map.map.find_path(combatant.hex(), hex).unwrap_or_default();
+}
+
+/// How far the combatants may still move, which only runs out during an encounter.
+#[derive(SystemParam)]
+pub struct MovementLeft<'w, 's> {
+    initiative: Res<'w, Initiative>,
+    combatants: Query<'w, 's, &'static Combatant>,
+}
+
+impl MovementLeft<'_, '_> {
+    fn in_encounter(&self) -> bool {
+        self.initiative.round() > 0
+    }
+}
+
+pub fn draw_token_path(
+    selected: Res<SelectedTool>,
+    mut drag: ResMut<TokenDrag>,
+    layout: Res<HexLayoutResource>,
+    movement: MovementLeft,
+    mut labels: Query<(&mut Text, &mut Transform)>,
+    mut gizmos: Gizmos,
+    mut commands: Commands,
+) {
+    if !matches!(**selected, Some(Tool::Move)) {
+        drag.combatant = None;
+    }
+    let remaining = drag.combatant
+        .and_then(|entity| movement.combatants.get(entity).ok())
+        .map(Combatant::remaining_movement);
+    let (Some(remaining), Some(&target)) = (remaining, drag.path.last()) else {
+        if let Some(label) = drag.label.take() {
+            commands.entity(label).despawn();
+        }
+        return;
+    };
+    let in_encounter = movement.in_encounter();
+    let reachable = !in_encounter || drag.cost <= remaining as u32;
+    let color = if reachable { REACHABLE_COLOR } else { UNREACHABLE_COLOR };
+    let points: Vec<Vec2> = drag.path.iter().map(|hex| layout.hex_to_world_pos(*hex)).collect();
+    gizmos.linestrip_2d(points.iter().copied(), color);
+    for point in &points {
+        gizmos.circle_2d(*point, 12., color);
+    }
+    let text = if in_encounter {
+        format!("{} ft / {} ft left", drag.cost, remaining)
+    } else {
+        format!("{} ft", drag.cost)
+    };
+    let position = layout.hex_to_world_pos(target).extend(LABEL_Z);
+    if let Some((mut label, mut transform)) = drag.label.and_then(|label| labels.get_mut(label).ok()) {
+        label.sections[0].value = text;
+        label.sections[0].style.color = color;
+        transform.translation = position;
+        return;
+    }
+    let label = commands.spawn((Text2dBundle {
+        text: Text::from_section(text, TextStyle {
+            font_size: 48.,
+            color,
+            ..default()
+        }),
+        transform: Transform::from_translation(position),
+        ..default()
+    }, RenderLayers::layer(ADMIN_LAYER))).id();
+    drag.label = Some(label);
+}
+
+/// Every combatant gets its full speed back when its turn starts.
+pub fn reset_movement_on_turn(
+    initiative: Res<Initiative>,
+    mut last_turn: Local<Option<(u32, Entity)>>,
+    mut combatants: Query<&mut Combatant>,
+) {
+    if !initiative.is_changed() { return; }
+    let turn = initiative.active_combatant().map(|entity| (initiative.round(), entity));
+    if turn == *last_turn { return; }
+    *last_turn = turn;
+    let Some((_, entity)) = turn else { return; };
+    if let Ok(mut combatant) = combatants.get_mut(entity) {
+        combatant.reset_movement();
+    }
+}
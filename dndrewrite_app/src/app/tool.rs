@@ -0,0 +1,52 @@
+use crate::map::combatant::CombatantType;
+use crate::map::tile::OverlayKind;
+use crate::model::id::Id;
+
+/// What a click on the admin window's map does.
+#[derive(Debug, Clone)]
+pub enum Tool {
+    Background(Id),
+    Overlay(OverlayKind, Id),
+    /// Drag across tiles to draw a river, road or path of the given family.
+    Linear(OverlayKind, Id),
+    /// Click to place a combatant with the figure, or remove one with shift.
+    Token(Id, CombatantType),
+    /// Drag a token to move it along the cheapest path within its speed.
+    Move,
+    /// Drag between two hexes to measure the distance.
+    Ruler,
+    /// Click a tile to show and edit its note.
+    Inspect,
+    /// Drag across tiles to reveal them to the players, or hide them with shift.
+    Fog,
+}
+
+impl Tool {
+    pub fn from_texture(id: Id) -> Self {
+        match OverlayKind::from_id(&id) {
+            Some(kind) if kind.is_linear() => Tool::Linear(kind, id.init()),
+            Some(OverlayKind::Figure) => Tool::Token(id, CombatantType::Enemy),
+            Some(kind) => Tool::Overlay(kind, id),
+            None => Tool::Background(id),
+        }
+    }
+
+    /// Figures are placed either as plain overlays or as tokens of any side.
+    pub fn next_figure_tool(&self) -> Option<Self> {
+        Some(match self {
+            Tool::Overlay(OverlayKind::Figure, id) => Tool::Token(id.clone(), CombatantType::Enemy),
+            Tool::Token(id, CombatantType::Enemy) => Tool::Token(id.clone(), CombatantType::Ally),
+            Tool::Token(id, CombatantType::Ally) => Tool::Token(id.clone(), CombatantType::Player),
+            Tool::Token(id, _) => Tool::Overlay(OverlayKind::Figure, id.clone()),
+            _ => return None,
+        })
+    }
+
+    /// The texture or family the tool paints with.
+    pub fn texture(&self) -> Option<&Id> {
+        match self {
+            Tool::Background(id) | Tool::Overlay(_, id) | Tool::Linear(_, id) | Tool::Token(id, _) => Some(id),
+            Tool::Move | Tool::Ruler | Tool::Inspect | Tool::Fog => None,
+        }
+    }
+}
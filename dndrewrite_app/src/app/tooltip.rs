@@ -0,0 +1,60 @@
+use bevy::prelude::*;
+use crate::app::resources::{AdminButtonMarker, AdminTooltip};
+use crate::view::query::UIQuery;
+
+const FONT_SIZE: f32 = 18.;
+/// Keeps the box from running off the right edge of the window.
+const MAX_WIDTH: f32 = 320.;
+
+pub fn spawn_tooltip(commands: &mut Commands, admin_camera: Entity) {
+    let text = commands.spawn(TextBundle::from_section("", TextStyle {
+        font_size: FONT_SIZE,
+        ..default()
+    })).id();
+    let node = commands.spawn((NodeBundle {
+        style: Style {
+            display: Display::None,
+            position_type: PositionType::Absolute,
+            bottom: Val::Vh(11.),
+            max_width: Val::Px(MAX_WIDTH),
+            padding: UiRect::all(Val::Px(6.)),
+            ..default()
+        },
+        background_color: BackgroundColor(Color::srgba(0., 0., 0., 0.85)),
+        z_index: ZIndex::Global(10),
+        ..default()
+    }, TargetCamera(admin_camera))).push_children(&[text]).id();
+    commands.insert_resource(AdminTooltip { node, text });
+}
+
+/// Shows the hovered admin button's tooltip above the bar, next to the cursor.
+pub fn show_tooltip(
+    ui: UIQuery,
+    buttons: Query<(&Interaction, &AdminButtonMarker)>,
+    tooltip: Res<AdminTooltip>,
+    mut styles: Query<&mut Style>,
+    mut texts: Query<&mut Text>,
+) {
+    let Ok(mut style) = styles.get_mut(tooltip.node) else { return; };
+    let hovered = buttons.iter()
+        .find(|(interaction, _)| **interaction != Interaction::None)
+        .map(|(_, button)| button);
+    let cursor = ui.get_focused_window()
+        .filter(|_| ui.is_admin_focused())
+        .and_then(|(_, window)| Some((window.cursor_position()?, window.width())));
+    let (Some(button), Some((cursor, width))) = (hovered, cursor) else {
+        if style.display != Display::None {
+            style.display = Display::None;
+        }
+        return;
+    };
+    let left = Val::Px(cursor.x.min(width - MAX_WIDTH).max(0.));
+    if style.display != Display::Flex || style.left != left {
+        style.display = Display::Flex;
+        style.left = left;
+    }
+    let Ok(mut text) = texts.get_mut(tooltip.text) else { return; };
+    if text.sections[0].value != button.tooltip {
+        text.sections[0].value = button.tooltip.clone();
+    }
+}
@@ -9,7 +9,27 @@ mod map;
 mod components;
 
 fn main() {
-    App::new()
+    //`dndrewrite generate <seed> [radius | <width>x<height>] [file] [--force]` only writes a new map
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    //`--map <file>` is the map to save to and load from
+    let map_path = map::resources::MapPath::resolve(&args);
+    if args.first().is_some_and(|command| command == "generate") {
+        match map::generator::run_headless(&args[1..], &map_path) {
+            Ok(path) => println!("Generated map '{}'.", path.display()),
+            Err(err) => {
+                eprintln!("{}", err);
+                std::process::exit(1);
+            }
+        }
+        return;
+    }
+    //`--assets <folder>` adds an asset pack, packs must exist before the asset plugin
+    let roots = model::resources::AssetRoots::resolve(&args);
+    let mut app = App::new();
+    roots.register(&mut app);
+    app
+        .insert_resource(roots)
+        .insert_resource(map_path)
         .add_plugins((
             DefaultPlugins
                 .set(WindowPlugin {
@@ -22,7 +42,7 @@ fn main() {
                     ..default()
                 }),
             model::plugins::LoaderPlugin,
-            view::plugins::UIPlugin))
-        .init_resource::<map::resources::Map>()
+            view::plugins::UIPlugin,
+            map::plugins::MapPlugin))
         .run();
 }
@@ -2,4 +2,23 @@ pub mod attributes;
 pub mod combatant;
 pub mod resources;
 pub mod tile;
-pub mod map;
\ No newline at end of file
+pub mod map;
+pub mod linear;
+pub mod fog;
+pub mod token;
+pub mod initiative;
+pub mod movement;
+pub mod brush;
+pub mod chunk;
+pub mod variant;
+pub mod season;
+pub mod generator;
+pub mod note;
+pub mod scale;
+pub mod history;
+pub mod document;
+pub mod errors;
+pub mod events;
+pub mod saving;
+pub mod query;
+pub mod plugins;
@@ -8,6 +8,21 @@ pub enum Size {
     Gargantuan,
 }
 
+impl Size {
+    /// How much bigger the token is drawn than a medium creature's.
+    pub fn scale(self) -> f32 {
+        match self {
+            Size::Tiny => 0.5,
+            Size::Small => 0.75,
+            Size::Medium => 1.,
+            Size::Large => 1.5,
+            Size::Huge => 2.,
+            Size::Gargantuan => 3.,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
 pub struct Hp {
     current: u16,
     max: u16,
@@ -15,6 +30,14 @@ pub struct Hp {
 }
 
 impl Hp {
+    pub fn new(max: u16) -> Self {
+        Hp {
+            current: max,
+            max,
+            temporary: 0,
+        }
+    }
+
     pub fn damage(&mut self, amount: u16) -> bool {
         let new_amount = amount.saturating_sub(self.temporary);
         self.temporary = self.temporary - (amount - new_amount);
@@ -34,4 +57,4 @@ impl Hp {
     pub fn set_temporary(&mut self, amount: u16) {
         self.temporary = amount;
     }
-}
\ No newline at end of file
+}
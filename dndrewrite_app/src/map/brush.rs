@@ -0,0 +1,136 @@
+use std::collections::VecDeque;
+use bevy::utils::HashSet;
+use hexx::Hex;
+use crate::model::id::Id;
+use super::*;
+
+pub const MAX_BRUSH_RADIUS: u32 = 10;
+
+/// How the background tool spreads the selected texture.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Default)]
+pub enum BrushShape {
+    /// Every hex within the radius of the cursor.
+    #[default]
+    Brush,
+    /// Every connected tile with the same background as the clicked one.
+    Fill,
+    /// Drag from one end to the other, as thick as the radius.
+    Line,
+    /// Drag between two corners, rows follow the pointy hexes.
+    Rectangle,
+    /// Drag from the center out to the ring.
+    Ring,
+}
+
+impl BrushShape {
+    pub const ALL: [BrushShape; 5] = [
+        BrushShape::Brush,
+        BrushShape::Fill,
+        BrushShape::Line,
+        BrushShape::Rectangle,
+        BrushShape::Ring,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            BrushShape::Brush => "brush",
+            BrushShape::Fill => "fill",
+            BrushShape::Line => "line",
+            BrushShape::Rectangle => "rectangle",
+            BrushShape::Ring => "ring",
+        }
+    }
+
+    pub fn next(self) -> Self {
+        Self::ALL[(self as usize + 1) % Self::ALL.len()]
+    }
+
+    /// Shapes that are dragged out and painted once the mouse is released.
+    pub fn is_dragged(self) -> bool {
+        matches!(self, BrushShape::Line | BrushShape::Rectangle | BrushShape::Ring)
+    }
+
+    /// The hexes a drag from `start` to `end` covers. Brush and fill cover
+    /// the area around `end` only.
+    pub fn hexes(self, start: Hex, end: Hex, radius: u32) -> Vec<Hex> {
+        let hexes: HashSet<Hex> = match self {
+            BrushShape::Brush => end.range(radius).collect(),
+            BrushShape::Fill => HashSet::from([end]),
+            BrushShape::Line => start.line_to(end).flat_map(|hex| hex.range(radius)).collect(),
+            BrushShape::Rectangle => rectangle(start, end).collect(),
+            BrushShape::Ring => start.ring(start.unsigned_distance_to(end)).collect(),
+        };
+        hexes.into_iter().collect()
+    }
+}
+
+/// Column of a hex in odd rows offset coordinates.
+fn column(hex: Hex) -> i32 {
+    hex.x + (hex.y - (hex.y & 1)) / 2
+}
+
+/// Column and row of a hex in odd rows offset coordinates.
+pub fn to_offset(hex: Hex) -> (i32, i32) {
+    (column(hex), hex.y)
+}
+
+pub fn from_offset(column: i32, row: i32) -> Hex {
+    Hex::new(column - (row - (row & 1)) / 2, row)
+}
+
+pub fn rectangle(a: Hex, b: Hex) -> impl Iterator<Item = Hex> {
+    let (left, right) = (column(a).min(column(b)), column(a).max(column(b)));
+    (a.y.min(b.y)..=a.y.max(b.y)).flat_map(move |y| {
+        (left..=right).map(move |col| from_offset(col, y))
+    })
+}
+
+impl resources::Map {
+    pub fn hexes(&self) -> impl Iterator<Item = Hex> + '_ {
+        self.tiles.keys().copied()
+            .chain(self.stored_tiles().map(|tile| tile.hex))
+    }
+
+    pub fn background(&self, hex: Hex) -> Option<&Id> {
+        match self.tiles.get(&hex) {
+            Some(tile) => Some(&tile.background.id),
+            None => self.stored_tile(hex).map(|tile| &tile.background),
+        }
+    }
+
+    /// Every tile reachable from `start` without crossing a background of
+    /// another family, variants of the same family count as one.
+    pub fn flood_fill(&self, start: Hex) -> Vec<Hex> {
+        let Some(family) = self.background(start).map(Id::init) else { return Vec::new(); };
+        let mut visited = HashSet::from([start]);
+        let mut queue = VecDeque::from([start]);
+        while let Some(hex) = queue.pop_front() {
+            for neighbor in hex.all_neighbors() {
+                let same_family = self.background(neighbor).is_some_and(|id| id.init() == family);
+                if same_family && visited.insert(neighbor) {
+                    queue.push_back(neighbor);
+                }
+            }
+        }
+        visited.into_iter().collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::map::document::TileDocument;
+    use crate::model::id::id;
+    use super::*;
+
+    #[test]
+    fn flood_fill_crosses_variants_of_the_same_family() {
+        let mut map = resources::Map::default();
+        map.store_tile(TileDocument::new(Hex::new(0, 0), id!("grass", "plain", "green", "1")));
+        map.store_tile(TileDocument::new(Hex::new(1, 0), id!("grass", "plain", "green", "7")));
+        map.store_tile(TileDocument::new(Hex::new(2, 0), id!("grass", "plain", "green", "3")));
+        map.store_tile(TileDocument::new(Hex::new(0, 1), id!("desert", "plain", "yellow", "1")));
+        let mut filled = map.flood_fill(Hex::new(0, 0));
+        filled.sort_by_key(|hex| (hex.y, hex.x));
+        assert_eq!(filled, [Hex::new(0, 0), Hex::new(1, 0), Hex::new(2, 0)]);
+    }
+}
@@ -0,0 +1,194 @@
+use bevy::prelude::*;
+use bevy::utils::{HashMap, HashSet};
+use hexx::Hex;
+use crate::map::brush::to_offset;
+use crate::map::combatant::Combatant;
+use crate::map::document::TileDocument;
+use crate::map::query::MapQuery;
+use crate::model::events::TexturesReloaded;
+use crate::model::resources::TextureTreeResource;
+use crate::view::query::UIQuery;
+use crate::view::resources::HexLayoutResource;
+use super::*;
+
+/// Width and height of a chunk, in offset columns and rows.
+pub const CHUNK_SIZE: i32 = 16;
+/// Past this many visible chunks a camera gets no margin around its view,
+/// zoomed out that far the margin would cost more than it hides.
+const MAX_CHUNKS_WITH_MARGIN: usize = 64;
+
+/// A square of offset coordinates tiles are spawned and stored together in.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub struct ChunkCoord(IVec2);
+
+impl ChunkCoord {
+    pub fn of(hex: Hex) -> Self {
+        let (column, row) = to_offset(hex);
+        Self(IVec2::new(column.div_euclid(CHUNK_SIZE), row.div_euclid(CHUNK_SIZE)))
+    }
+}
+
+impl resources::Map {
+    pub(super) fn stored_tile(&self, hex: Hex) -> Option<&TileDocument> {
+        self.stored.get(&ChunkCoord::of(hex))?.get(&hex)
+    }
+
+    pub(super) fn stored_tile_mut(&mut self, hex: Hex) -> Option<&mut TileDocument> {
+        self.stored.get_mut(&ChunkCoord::of(hex))?.get_mut(&hex)
+    }
+
+    pub(super) fn stored_tiles(&self) -> impl Iterator<Item = &TileDocument> {
+        self.stored.values().flat_map(HashMap::values)
+    }
+
+    /// Keeps a tile as a document only, it is spawned with the rest of its
+    /// chunk once a camera comes close.
+    pub(super) fn store_tile(&mut self, tile: TileDocument) {
+        self.stored.entry(ChunkCoord::of(tile.hex)).or_default().insert(tile.hex, tile);
+    }
+
+    /// Spawns the chunk holding `hex` if it is only stored, so its sprites can
+    /// be edited.
+    pub(super) fn wake(
+        &mut self,
+        commands: &mut Commands,
+        texture_tree: &TextureTreeResource,
+        layout: &HexLayoutResource,
+        hex: Hex,
+    ) {
+        let Some(tiles) = self.stored.remove(&ChunkCoord::of(hex)) else { return; };
+        for tile in tiles.into_values() {
+            self.load_tile(commands, texture_tree, layout, tile);
+        }
+    }
+
+    /// Stores every spawned chunk, they are spawned again with the current
+    /// textures by the next `stream_chunks`.
+    pub fn respawn(&mut self, commands: &mut Commands) {
+        let hexes: Vec<Hex> = self.tiles.keys().copied().collect();
+        self.stow(commands, hexes);
+    }
+
+    /// Despawns spawned tiles, keeping them as documents.
+    fn stow(&mut self, commands: &mut Commands, hexes: Vec<Hex>) {
+        for hex in hexes {
+            let Some(tile) = self.tile_document(hex) else { continue; };
+            self.despawn_tile(commands, hex);
+            self.store_tile(tile);
+        }
+    }
+
+    /// Spawns the wanted chunks and stores every other one, despawning its
+    /// entities but keeping its tiles.
+    fn stream(
+        &mut self,
+        commands: &mut Commands,
+        texture_tree: &TextureTreeResource,
+        layout: &HexLayoutResource,
+        wanted: &HashSet<ChunkCoord>,
+    ) {
+        let unwanted: Vec<Hex> = self.tiles.keys()
+            .filter(|hex| !wanted.contains(&ChunkCoord::of(**hex)))
+            .copied()
+            .collect();
+        self.stow(commands, unwanted);
+        for chunk in wanted {
+            let Some(hex) = self.stored.get(chunk).and_then(|tiles| tiles.keys().next().copied()) else { continue; };
+            self.wake(commands, texture_tree, layout, hex);
+        }
+    }
+}
+
+/// Every chunk a camera shows, with one chunk of margin around them unless
+/// there are already many.
+fn chunks_in_view(layout: &HexLayoutResource, view: Rect) -> Vec<ChunkCoord> {
+    let corners = [
+        view.min,
+        view.max,
+        Vec2::new(view.min.x, view.max.y),
+        Vec2::new(view.max.x, view.min.y),
+    ].map(|corner| ChunkCoord::of(layout.world_pos_to_hex(corner)).0);
+    let mut min = corners.into_iter().reduce(IVec2::min).unwrap();
+    let mut max = corners.into_iter().reduce(IVec2::max).unwrap();
+    let visible = (max - min + IVec2::ONE).element_product() as usize;
+    //The margin keeps panning from showing the edge being spawned
+    if visible <= MAX_CHUNKS_WITH_MARGIN {
+        min -= IVec2::ONE;
+        max += IVec2::ONE;
+    }
+    (min.y..=max.y)
+        .flat_map(|y| (min.x..=max.x).map(move |x| ChunkCoord(IVec2::new(x, y))))
+        .collect()
+}
+
+/// Keeps only the chunks near either camera spawned.
+pub fn stream_chunks(ui: UIQuery, mut map: MapQuery) {
+    let wanted: HashSet<ChunkCoord> = [true, false].into_iter()
+        .filter_map(|admin| ui.visible_world_rect(admin))
+        .flat_map(|view| chunks_in_view(&map.layout, view))
+        .collect();
+    let MapQuery { map, commands, texture_tree, layout, .. } = &mut map;
+    //Most frames nothing has to change, leave the map unmarked then
+    let settled = map.tiles.keys().all(|hex| wanted.contains(&ChunkCoord::of(*hex)))
+        && wanted.iter().all(|chunk| !map.stored.contains_key(chunk));
+    if settled { return; }
+    map.stream(commands, texture_tree, layout, &wanted);
+}
+
+/// Redraws the map and its tokens with the textures that were reloaded.
+pub fn refresh_textures(
+    mut reloaded: EventReader<TexturesReloaded>,
+    mut map: ResMut<resources::Map>,
+    texture_tree: Res<TextureTreeResource>,
+    mut tokens: Query<(&Combatant, &mut Handle<Image>, &mut TextureAtlas)>,
+    mut commands: Commands,
+) {
+    if reloaded.read().count() == 0 { return; }
+    map.respawn(&mut commands);
+    for (combatant, mut image, mut atlas) in &mut tokens {
+        let Some(leaf) = texture_tree.get(combatant.texture()).and_then(|node| node.leaf()) else {
+            warn!("Figure '{}' is gone, keeping its old texture.", combatant.texture());
+            continue;
+        };
+        *image = leaf.image;
+        *atlas = leaf.atlas;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::ecs::world::CommandQueue;
+    use crate::model::id::{Id, id};
+    use crate::model::texture_tree::{TextureLeaf, TextureNode};
+    use super::*;
+
+    #[test]
+    fn streamed_chunks_keep_one_copy_of_each_tile() {
+        let mut world = World::new();
+        let mut queue = CommandQueue::default();
+        let mut commands = Commands::new(&mut queue, &world);
+        let texture_tree = TextureTreeResource(TextureNode::new(bevy::utils::hashbrown::HashMap::from([
+            (id!("grass", "plain", "green", "1"), TextureLeaf::default()),
+        ])));
+        let layout = HexLayoutResource::default();
+        let hexes = [Hex::new(0, 0), Hex::new(1, 0)];
+        let chunk = ChunkCoord::of(hexes[0]);
+        assert_eq!(chunk, ChunkCoord::of(hexes[1]));
+        let mut map = resources::Map::default();
+        for hex in hexes {
+            map.store_tile(TileDocument::new(hex, id!("grass", "plain", "green", "1")));
+        }
+
+        map.stream(&mut commands, &texture_tree, &layout, &[chunk].into_iter().collect());
+        assert_eq!(map.tiles.len(), 2);
+        assert!(map.stored.is_empty());
+
+        map.stream(&mut commands, &texture_tree, &layout, &HashSet::new());
+        assert!(map.tiles.is_empty());
+        assert_eq!(map.stored[&chunk].len(), 2);
+        let document = map.to_document();
+        let saved: Vec<Hex> = document.tiles.iter().map(|tile| tile.hex).collect();
+        assert_eq!(saved, hexes);
+        queue.apply(&mut world);
+    }
+}
@@ -1,7 +1,11 @@
-use bevy::prelude::Entity;
+use bevy::prelude::{Component, Entity};
+use hexx::Hex;
 use crate::map::attributes::{Hp, Size};
+use crate::model::id::Id;
 
-#[derive(Debug, Copy, Clone)]
+const DEFAULT_SPEED: u16 = 30;
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
 pub enum CombatantType {
     Player,
     Summon,
@@ -9,12 +13,85 @@ pub enum CombatantType {
     Ally,
 }
 
+#[derive(Component, Debug, Clone)]
 pub struct Combatant {
-    texture: usize,
+    texture: Id,
+    hex: Hex,
     summoner: Option<Entity>,
     summons: Option<Entity>,
     name: String,
     size: Size,
     hp: Hp,
     combatant_type: CombatantType,
-}
\ No newline at end of file
+    initiative_bonus: i8,
+    /// Walking speed in feet.
+    speed: u16,
+    /// Feet already moved this turn.
+    movement_used: u16,
+}
+
+impl Combatant {
+    pub fn new(name: impl Into<String>, texture: Id, size: Size, hp: Hp, combatant_type: CombatantType) -> Self {
+        Combatant {
+            texture,
+            hex: Hex::ZERO,
+            summoner: None,
+            summons: None,
+            name: name.into(),
+            size,
+            hp,
+            combatant_type,
+            initiative_bonus: 0,
+            speed: DEFAULT_SPEED,
+            movement_used: 0,
+        }
+    }
+
+    /// Names a combatant after its figure, `overlay/figure/standard_full/adult_dracolich_blue_130`
+    /// becomes `adult dracolich blue`.
+    pub fn name_from_texture(texture: &Id) -> String {
+        let file = texture.last().map(String::as_str).unwrap_or_default();
+        let name = file.trim_end_matches(|c: char| c.is_ascii_digit()).trim_end_matches('_');
+        name.replace('_', " ")
+    }
+
+    pub fn texture(&self) -> &Id {
+        &self.texture
+    }
+
+    pub fn hex(&self) -> Hex {
+        self.hex
+    }
+
+    pub(super) fn set_hex(&mut self, hex: Hex) {
+        self.hex = hex;
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn size(&self) -> Size {
+        self.size
+    }
+
+    pub fn combatant_type(&self) -> CombatantType {
+        self.combatant_type
+    }
+
+    pub fn initiative_bonus(&self) -> i8 {
+        self.initiative_bonus
+    }
+
+    pub fn remaining_movement(&self) -> u16 {
+        self.speed.saturating_sub(self.movement_used)
+    }
+
+    pub fn spend_movement(&mut self, feet: u16) {
+        self.movement_used += feet;
+    }
+
+    pub fn reset_movement(&mut self) {
+        self.movement_used = 0;
+    }
+}
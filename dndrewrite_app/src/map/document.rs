@@ -0,0 +1,141 @@
+use std::path::Path;
+use hexx::Hex;
+use serde::{Deserialize, Serialize};
+use crate::map::note::Note;
+use crate::map::scale::MapScale;
+use crate::map::season::Season;
+use crate::model::id::Id;
+use super::*;
+
+/// Current on-disk format version. Bump it whenever [`MapDocument`] changes
+/// in a way old files can't express and add the upgrade step to [`MIGRATIONS`].
+pub const FORMAT_VERSION: u32 = 2;
+
+/// `MIGRATIONS[n]` upgrades a document from version `n + 1` to `n + 2`.
+const MIGRATIONS: &[fn(&mut MapDocument)] = &[
+    //Maps from before the fog of war were fully visible to the players
+    |document| document.tiles.iter_mut().for_each(|tile| tile.revealed = true),
+];
+
+const _: () = assert!(MIGRATIONS.len() as u32 == FORMAT_VERSION - 1);
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct MapDocument {
+    pub version: u32,
+    #[serde(default)]
+    pub seed: u64,
+    #[serde(default)]
+    pub season: Season,
+    /// Miles across one hex.
+    #[serde(default)]
+    pub scale: MapScale,
+    #[serde(default)]
+    pub tiles: Vec<TileDocument>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct TileDocument {
+    pub hex: Hex,
+    pub background: Id,
+    #[serde(default)]
+    pub overlays: Vec<Id>,
+    #[serde(default)]
+    pub text: Option<String>,
+    #[serde(default)]
+    pub revealed: bool,
+    #[serde(default)]
+    pub note: Option<Note>,
+}
+
+impl TileDocument {
+    /// A tile with nothing but its background, hidden from the players.
+    pub fn new(hex: Hex, background: Id) -> Self {
+        Self {
+            hex,
+            background,
+            overlays: Vec::new(),
+            text: None,
+            revealed: false,
+            note: None,
+        }
+    }
+}
+
+impl Default for MapDocument {
+    fn default() -> Self {
+        Self {
+            version: FORMAT_VERSION,
+            seed: 0,
+            season: Season::default(),
+            scale: MapScale::default(),
+            tiles: Vec::new(),
+        }
+    }
+}
+
+impl MapDocument {
+    pub fn read(path: impl AsRef<Path>) -> Result<Self, errors::MapFileError> {
+        let contents = std::fs::read_to_string(path)?;
+        let mut document: MapDocument = ron::from_str(&contents)?;
+        document.migrate()?;
+        Ok(document)
+    }
+
+    pub fn write(&self, path: impl AsRef<Path>) -> Result<(), errors::MapFileError> {
+        let path = path.as_ref();
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let contents = ron::ser::to_string_pretty(self, ron::ser::PrettyConfig::default())?;
+        std::fs::write(path, contents)?;
+        Ok(())
+    }
+
+    fn migrate(&mut self) -> Result<(), errors::MapFileError> {
+        if self.version == 0 || self.version > FORMAT_VERSION {
+            return Err(errors::MapFileError::UnsupportedVersion(self.version));
+        }
+        for migration in &MIGRATIONS[self.version as usize - 1..] {
+            migration(self);
+            self.version += 1;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::model::id::id;
+    use super::*;
+
+    fn document(version: u32) -> MapDocument {
+        MapDocument {
+            version,
+            tiles: vec![TileDocument::new(Hex::ZERO, id!("grass", "plain", "green", "1"))],
+            ..MapDocument::default()
+        }
+    }
+
+    #[test]
+    fn old_documents_are_migrated_to_the_current_version() {
+        let mut document = document(1);
+        document.migrate().unwrap();
+        assert_eq!(document.version, FORMAT_VERSION);
+        //Version 1 had no fog of war
+        assert!(document.tiles[0].revealed);
+    }
+
+    #[test]
+    fn current_documents_are_left_alone() {
+        let mut document = document(FORMAT_VERSION);
+        document.migrate().unwrap();
+        assert_eq!(document.version, FORMAT_VERSION);
+        assert!(!document.tiles[0].revealed);
+    }
+
+    #[test]
+    fn unknown_versions_are_rejected() {
+        assert!(document(0).migrate().is_err());
+        assert!(document(FORMAT_VERSION + 1).migrate().is_err());
+    }
+}
@@ -0,0 +1,25 @@
+#[derive(Debug)]
+pub enum MapFileError {
+    IoError(std::io::Error),
+    SerializeError(ron::Error),
+    DeserializeError(ron::error::SpannedError),
+    UnsupportedVersion(u32),
+}
+
+impl From<std::io::Error> for MapFileError {
+    fn from(value: std::io::Error) -> Self {
+        MapFileError::IoError(value)
+    }
+}
+
+impl From<ron::Error> for MapFileError {
+    fn from(value: ron::Error) -> Self {
+        MapFileError::SerializeError(value)
+    }
+}
+
+impl From<ron::error::SpannedError> for MapFileError {
+    fn from(value: ron::error::SpannedError) -> Self {
+        MapFileError::DeserializeError(value)
+    }
+}
@@ -0,0 +1,12 @@
+use std::path::PathBuf;
+use bevy::prelude::Event;
+use super::*;
+
+#[derive(Event, Debug, Clone)]
+pub struct SaveMap(pub PathBuf);
+
+#[derive(Event, Debug, Clone)]
+pub struct LoadMap(pub PathBuf);
+
+#[derive(Event, Debug, Clone)]
+pub struct GenerateMap(pub generator::GeneratorSettings);
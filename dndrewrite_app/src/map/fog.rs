@@ -0,0 +1,66 @@
+use bevy::prelude::*;
+use bevy::render::view::RenderLayers;
+use hexx::Hex;
+use crate::model::id::{Id, id};
+use crate::model::resources::TextureTreeResource;
+use crate::model::texture_tree::TextureLeaf;
+use crate::view::layers::USER_LAYER;
+use crate::view::resources::HexLayoutResource;
+use super::*;
+
+const FOG_Z: f32 = 3.;
+
+fn fog_family() -> Id {
+    id!["clouds", "base", "white"]
+}
+
+/// Spawns the clouds hiding a tile from the players, picking one of the cloud
+/// variants by position so the fog doesn't look tiled.
+pub(super) fn spawn_fog(
+    commands: &mut Commands,
+    texture_tree: &TextureTreeResource,
+    layout: &HexLayoutResource,
+    hex: Hex,
+) -> Entity {
+    let variants: Vec<TextureLeaf> = texture_tree.get(&fog_family())
+        .and_then(|node| node.branch())
+        .map(|branch| branch.values().filter_map(|node| node.leaf()).collect())
+        .unwrap_or_default();
+    let transform = Transform::from_translation(layout.hex_to_world_pos(hex).extend(FOG_Z));
+    let mut fog = match variants.len() {
+        //Without cloud textures the tile is simply blacked out
+        0 => commands.spawn(SpriteBundle {
+            sprite: Sprite {
+                color: Color::BLACK,
+                custom_size: Some(layout.hex_size * 2.),
+                ..default()
+            },
+            transform,
+            ..default()
+        }),
+        len => commands.spawn(variants[(hex.x * 7 + hex.y * 13).rem_euclid(len as i32) as usize].sprite(transform)),
+    };
+    fog.insert(RenderLayers::layer(USER_LAYER)).id()
+}
+
+impl resources::Map {
+    pub fn set_revealed(
+        &mut self,
+        commands: &mut Commands,
+        texture_tree: &TextureTreeResource,
+        layout: &HexLayoutResource,
+        hex: Hex,
+        revealed: bool,
+    ) {
+        self.wake(commands, texture_tree, layout, hex);
+        let Some(tile) = self.tiles.get_mut(&hex) else { return };
+        match (revealed, tile.fog) {
+            (true, Some(fog)) => {
+                commands.entity(fog).despawn();
+                tile.fog = None;
+            }
+            (false, None) => tile.fog = Some(spawn_fog(commands, texture_tree, layout, hex)),
+            _ => {}
+        }
+    }
+}
@@ -0,0 +1,324 @@
+use std::path::PathBuf;
+use bevy::prelude::*;
+use bevy::utils::HashMap;
+use hexx::{Hex, HexLayout, HexOrientation};
+use crate::map::brush::{from_offset, rectangle};
+use crate::map::document::{MapDocument, TileDocument};
+use crate::map::linear::edge_bit;
+use crate::map::variant::mix;
+use crate::model::id::{Id, id};
+use super::*;
+
+const DEFAULT_RADIUS: u32 = 24;
+/// Size of the largest noise features, in hexes.
+const NOISE_SCALE: f32 = 12.;
+const OCTAVES: u32 = 4;
+const SEA_LEVEL: f32 = 0.4;
+/// Hexes per river source, counting land only.
+const HEXES_PER_RIVER: usize = 150;
+
+#[derive(Debug, Copy, Clone)]
+pub enum MapShape {
+    Hexagon { radius: u32 },
+    /// Odd rows offset, like the rectangle brush.
+    Rectangle { width: u32, height: u32 },
+}
+
+impl MapShape {
+    fn hexes(self) -> Vec<Hex> {
+        match self {
+            MapShape::Hexagon { radius } => Hex::ZERO.range(radius).collect(),
+            MapShape::Rectangle { width, height } => {
+                let corner = from_offset(width.max(1) as i32 - 1, height.max(1) as i32 - 1);
+                rectangle(Hex::ZERO, corner).collect()
+            }
+        }
+    }
+}
+
+#[derive(Debug, Copy, Clone)]
+pub struct GeneratorSettings {
+    pub seed: u64,
+    pub shape: MapShape,
+}
+
+impl Default for GeneratorSettings {
+    fn default() -> Self {
+        Self {
+            seed: 0,
+            shape: MapShape::Hexagon { radius: DEFAULT_RADIUS },
+        }
+    }
+}
+
+/// Hash of a lattice point as a value between 0 and 1.
+fn lattice(seed: u64, x: i32, y: i32) -> f32 {
+    let hash = mix(mix(seed ^ x as u32 as u64) ^ ((y as u32 as u64) << 32));
+    (hash >> 40) as f32 / (1u64 << 24) as f32
+}
+
+/// Smoothly interpolated value noise between 0 and 1.
+fn value_noise(seed: u64, position: Vec2) -> f32 {
+    let cell = position.floor();
+    let t = position - cell;
+    let t = t * t * (Vec2::splat(3.) - 2. * t);
+    let (x, y) = (cell.x as i32, cell.y as i32);
+    let top = lattice(seed, x, y).lerp(lattice(seed, x + 1, y), t.x);
+    let bottom = lattice(seed, x, y + 1).lerp(lattice(seed, x + 1, y + 1), t.x);
+    top.lerp(bottom, t.y)
+}
+
+/// Layers of value noise, the finer ones weighing less.
+fn fractal_noise(seed: u64, position: Vec2) -> f32 {
+    let (mut total, mut weight, mut amplitude, mut frequency) = (0., 0., 1., 1.);
+    for octave in 0..OCTAVES {
+        total += value_noise(mix(seed + octave as u64), position * frequency) * amplitude;
+        weight += amplitude;
+        amplitude /= 2.;
+        frequency *= 2.;
+    }
+    total / weight
+}
+
+/// Picks the tile family for a hex, `height` is 0 at the shore and 1 at the
+/// highest peaks.
+fn biome(height: f32, moisture: f32) -> Id {
+    let (tile_type, variant) = match (height, moisture) {
+        (h, m) if h > 0.75 && m < 0.25 => ("desert", "mountain"),
+        (h, m) if h > 0.75 && m > 0.6 => ("pine_forest", "mountain"),
+        (h, _) if h > 0.75 => ("hills", "mountain"),
+        (h, m) if h > 0.5 && m < 0.35 => ("hills", "sparse"),
+        (h, m) if h > 0.5 && m < 0.6 => ("hills", "clearing"),
+        (h, _) if h > 0.5 => ("pine_forest", "dense"),
+        (_, m) if m < 0.2 => ("desert", "sparse"),
+        (_, m) if m < 0.28 => ("desert", "clearing"),
+        (_, m) if m < 0.4 => ("grassland", "sparse"),
+        (_, m) if m < 0.5 => ("grassland", "clearing"),
+        (_, m) if m < 0.6 => ("oak_forest", "sparse"),
+        (_, m) if m < 0.72 => ("mixed_forest", "dense"),
+        (h, _) if h < 0.15 => ("swamp", "dense"),
+        _ => ("jungle_forest", "dense"),
+    };
+    let color = if tile_type == "desert" { "yellow" } else { "green" };
+    id![tile_type, variant, color]
+}
+
+/// Generates a new map. The same settings always give the same map, tiles
+/// name whole families and get their variant from the seed when placed.
+pub fn generate(settings: GeneratorSettings) -> MapDocument {
+    let layout = HexLayout {
+        orientation: HexOrientation::Pointy,
+        ..default()
+    };
+    let hexes = settings.shape.hexes();
+    let position = |hex: Hex| {
+        let world = layout.hex_to_world_pos(hex);
+        world / (layout.hex_size * 2.)
+    };
+    let center = hexes.iter().map(|hex| position(*hex)).sum::<Vec2>() / hexes.len().max(1) as f32;
+    let extent = hexes.iter().map(|hex| position(*hex).distance(center)).fold(1., f32::max);
+    let elevation_seed = mix(settings.seed);
+    let moisture_seed = mix(elevation_seed);
+    let elevation: HashMap<Hex, f32> = hexes.iter()
+        .map(|hex| {
+            //Lowering the edges keeps the land away from the map's border
+            let falloff = (position(*hex).distance(center) / extent).powi(2);
+            let noise = fractal_noise(elevation_seed, position(*hex) / NOISE_SCALE);
+            (*hex, noise * 1.2 - falloff * 0.6)
+        })
+        .collect();
+    let is_land = |hex: &Hex| elevation.get(hex).is_some_and(|height| *height >= SEA_LEVEL);
+    let peak = elevation.values().copied().fold(SEA_LEVEL + f32::EPSILON, f32::max);
+    let mut tiles: Vec<TileDocument> = hexes.iter()
+        .map(|hex| {
+            let height = elevation[hex];
+            let background = if height < SEA_LEVEL - 0.1 {
+                id!["ocean", "big", "blue"]
+            } else if height < SEA_LEVEL {
+                id!["ocean", "small", "blue"]
+            } else {
+                let moisture = fractal_noise(moisture_seed, position(*hex) / NOISE_SCALE);
+                biome((height - SEA_LEVEL) / (peak - SEA_LEVEL), moisture)
+            };
+            TileDocument::new(*hex, background)
+        })
+        .collect();
+    let rivers = rivers(&layout, settings.seed, &elevation, &is_land);
+    for tile in &mut tiles {
+        if let Some(mask) = rivers.get(&tile.hex) {
+            tile.overlays.push(id!["overlay", "river", "blue"].extend(format!("{:06b}", mask)));
+        }
+    }
+    tiles.sort_by_key(|tile| (tile.hex.y, tile.hex.x));
+    MapDocument {
+        seed: settings.seed,
+        tiles,
+        ..default()
+    }
+}
+
+/// Runs rivers from the highest land downhill, until they reach the sea or
+/// get stuck in a hollow. Returns the edge mask of every hex with a river.
+fn rivers(
+    layout: &HexLayout,
+    seed: u64,
+    elevation: &HashMap<Hex, f32>,
+    is_land: &impl Fn(&Hex) -> bool,
+) -> HashMap<Hex, u8> {
+    let mut sources: Vec<Hex> = elevation.keys().copied().filter(is_land).collect();
+    let count = sources.len() / HEXES_PER_RIVER;
+    //Highest first, ties settled by the seed so the order doesn't depend on the hash map
+    sources.sort_by(|a, b| elevation[b].total_cmp(&elevation[a])
+        .then_with(|| mix(seed ^ a.x as u32 as u64 ^ ((a.y as u32 as u64) << 32))
+            .cmp(&mix(seed ^ b.x as u32 as u64 ^ ((b.y as u32 as u64) << 32)))));
+    let mut masks: HashMap<Hex, u8> = HashMap::new();
+    let mut started = Vec::new();
+    for source in sources {
+        if started.len() >= count { break; }
+        //Sources too close to each other would run side by side
+        if started.iter().any(|other: &Hex| other.unsigned_distance_to(source) < 6) { continue; }
+        //Springing from the middle of another river would make a junction
+        if masks.contains_key(&source) { continue; }
+        started.push(source);
+        let mut current = source;
+        while is_land(&current) {
+            let Some(next) = current.all_neighbors().into_iter()
+                .filter(|neighbor| elevation.contains_key(neighbor))
+                .min_by(|a, b| elevation[a].total_cmp(&elevation[b]))
+                .filter(|next| elevation[next] < elevation[&current]) else { break; };
+            let (Some(out), Some(into)) = (edge_bit(layout, current, next), edge_bit(layout, next, current)) else { break; };
+            let joined = masks.get(&next).copied();
+            //The packs have no junctions, a river reaching the middle of
+            //another one ends beside it
+            if joined.is_some_and(|mask| mask.count_ones() >= 2) { break; }
+            *masks.entry(current).or_default() |= out;
+            if is_land(&next) {
+                *masks.entry(next).or_default() |= into;
+            }
+            //Rivers merge into the first river they meet
+            if joined.is_some() { break; }
+            current = next;
+        }
+    }
+    masks
+}
+
+/// Generates a map straight into a file, without opening any window.
+/// Expects `<seed> [radius | <width>x<height>] [file] [--force]`, without a
+/// file the map is written next to `map_path` as `generated_<seed>.ron`.
+/// Existing files are only overwritten with `--force`.
+pub fn run_headless(args: &[String], map_path: &resources::MapPath) -> Result<PathBuf, String> {
+    let mut force = false;
+    let mut positional = Vec::new();
+    let mut rest = args.iter();
+    while let Some(arg) = rest.next() {
+        match arg.as_str() {
+            "--force" => force = true,
+            //Already in `map_path`
+            saving::MAP_ARG => { rest.next(); }
+            _ => positional.push(arg),
+        }
+    }
+    let args = positional;
+    let seed: u64 = args.first()
+        .ok_or("Missing the seed.")?
+        .parse().map_err(|_| "The seed has to be a number.")?;
+    let shape = match args.get(1).map(|size| size.split_once('x')) {
+        None => MapShape::Hexagon { radius: DEFAULT_RADIUS },
+        Some(None) => MapShape::Hexagon {
+            radius: args[1].parse().map_err(|_| "The radius has to be a number.")?,
+        },
+        Some(Some((width, height))) => MapShape::Rectangle {
+            width: width.parse().map_err(|_| "The width has to be a number.")?,
+            height: height.parse().map_err(|_| "The height has to be a number.")?,
+        },
+    };
+    let path = match args.get(2) {
+        Some(path) => PathBuf::from(path),
+        None => map_path.0.with_file_name(format!("generated_{}.ron", seed)),
+    };
+    if path.exists() && !force {
+        return Err(format!("'{}' already exists, pass --force to overwrite it.", path.display()));
+    }
+    let document = generate(GeneratorSettings { seed, shape });
+    document.write(&path).map_err(|err| format!("{:?}", err))?;
+    Ok(path)
+}
+
+pub fn generate_shortcut(
+    keys: Res<ButtonInput<KeyCode>>,
+    mut generate_events: EventWriter<events::GenerateMap>,
+) {
+    if !keys.any_pressed([KeyCode::ControlLeft, KeyCode::ControlRight]) { return; }
+    if keys.just_pressed(KeyCode::KeyG) {
+        generate_events.send(events::GenerateMap(GeneratorSettings {
+            seed: rand::random(),
+            ..default()
+        }));
+    }
+}
+
+pub fn generate_map(
+    mut map: query::MapQuery,
+    mut generate_events: EventReader<events::GenerateMap>,
+) {
+    for events::GenerateMap(settings) in generate_events.read() {
+        let document = generate(*settings);
+        info!("Generated {} tiles from seed {}.", document.tiles.len(), settings.seed);
+        map.replace_tiles(document);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn settings(seed: u64) -> GeneratorSettings {
+        GeneratorSettings {
+            seed,
+            shape: MapShape::Hexagon { radius: 12 },
+        }
+    }
+
+    #[test]
+    fn same_seed_gives_the_same_map() {
+        let first = generate(settings(42));
+        let second = generate(settings(42));
+        assert!(!first.tiles.is_empty());
+        assert_eq!(first.seed, second.seed);
+        assert_eq!(first.tiles, second.tiles);
+    }
+
+    #[test]
+    fn different_seeds_give_different_maps() {
+        assert_ne!(generate(settings(42)).tiles, generate(settings(43)).tiles);
+    }
+
+    #[test]
+    fn every_river_piece_has_a_sprite() {
+        //Masks are the `_c<mask>_` part of the sprite names
+        let folder = std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("../assets/pointy.overlay_rivers.blue");
+        let sprites: Vec<String> = std::fs::read_dir(folder).unwrap()
+            .filter_map(|entry| {
+                let name = entry.ok()?.file_name().into_string().ok()?;
+                Some(name.split("_c").nth(1)?.get(..6)?.to_owned())
+            })
+            .collect();
+        assert!(!sprites.is_empty());
+        let river = id!["overlay", "river", "blue"];
+        let mut pieces = 0;
+        for seed in 0..20 {
+            let document = generate(GeneratorSettings {
+                seed,
+                shape: MapShape::Hexagon { radius: 40 },
+            });
+            for overlay in document.tiles.iter().flat_map(|tile| &tile.overlays) {
+                if overlay.init() != river { continue; }
+                let mask = overlay.last().unwrap();
+                assert!(sprites.contains(mask), "No river sprite for {}", mask);
+                pieces += 1;
+            }
+        }
+        assert!(pieces > 0);
+    }
+}
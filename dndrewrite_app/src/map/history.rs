@@ -0,0 +1,243 @@
+use bevy::prelude::*;
+use hexx::Hex;
+use crate::map::combatant::Combatant;
+use crate::map::document::TileDocument;
+use crate::map::query::MapQuery;
+use crate::map::season::Season;
+use crate::view::query::UIQuery;
+use super::*;
+
+/// Oldest groups are forgotten past this many.
+const MAX_HISTORY: usize = 200;
+
+/// A reversible change to the map, the state of one tile or token before and
+/// after it. `None` means there was nothing there.
+#[derive(Debug, Clone)]
+pub enum MapEdit {
+    Tile {
+        hex: Hex,
+        before: Option<TileDocument>,
+        after: Option<TileDocument>,
+    },
+    Token {
+        entity: Entity,
+        before: Option<Combatant>,
+        after: Option<Combatant>,
+    },
+    /// Only the setting, the repainted tiles are recorded next to it.
+    Season {
+        before: Season,
+        after: Season,
+    },
+}
+
+impl MapEdit {
+    fn reversed(self) -> Self {
+        match self {
+            MapEdit::Tile { hex, before, after } => MapEdit::Tile { hex, before: after, after: before },
+            MapEdit::Token { entity, before, after } => MapEdit::Token { entity, before: after, after: before },
+            MapEdit::Season { before, after } => MapEdit::Season { before: after, after: before },
+        }
+    }
+}
+
+impl resources::EditHistory {
+    pub fn record(&mut self, edit: MapEdit) {
+        let unchanged = match &edit {
+            MapEdit::Tile { before, after, .. } => before == after,
+            MapEdit::Token { before, after, .. } => before.is_none() && after.is_none(),
+            MapEdit::Season { before, after } => before == after,
+        };
+        if unchanged { return; }
+        self.pending.push(edit);
+        self.redo.clear();
+    }
+
+    /// Closes the current group, the next edit starts a new one.
+    pub fn commit(&mut self) {
+        if self.pending.is_empty() { return; }
+        self.undo.push(std::mem::take(&mut self.pending));
+        if self.undo.len() > MAX_HISTORY {
+            self.undo.remove(0);
+        }
+    }
+
+    pub fn clear(&mut self) {
+        self.undo.clear();
+        self.redo.clear();
+        self.pending.clear();
+    }
+
+    /// Respawned tokens get a new entity, older edits have to follow it.
+    fn remap(&mut self, old: Entity, new: Entity) {
+        let edits = self.undo.iter_mut().chain(self.redo.iter_mut()).flatten();
+        for edit in edits {
+            if let MapEdit::Token { entity, .. } = edit {
+                if *entity == old {
+                    *entity = new;
+                }
+            }
+        }
+    }
+}
+
+impl<'w, 's> MapQuery<'w, 's> {
+    pub fn undo(&mut self) {
+        self.history.commit();
+        let Some(group) = self.history.undo.pop() else { return; };
+        info!("Undoing {} edits.", group.len());
+        let group = self.apply(group.into_iter().rev().map(MapEdit::reversed).collect());
+        self.history.redo.push(group.into_iter().rev().map(MapEdit::reversed).collect());
+    }
+
+    pub fn redo(&mut self) {
+        self.history.commit();
+        let Some(group) = self.history.redo.pop() else { return; };
+        info!("Redoing {} edits.", group.len());
+        let group = self.apply(group);
+        self.history.undo.push(group);
+    }
+
+    /// Brings every edit's subject to its `after` state, returning the edits
+    /// with their entities updated.
+    fn apply(&mut self, group: Vec<MapEdit>) -> Vec<MapEdit> {
+        let mut applied = Vec::with_capacity(group.len());
+        let mut respawned: Vec<(Entity, Entity)> = Vec::new();
+        for edit in group {
+            let edit = match edit {
+                MapEdit::Tile { hex, before, after } => {
+                    match after.clone() {
+                        Some(tile) => self.map.load_tile(&mut self.commands, &self.texture_tree, &self.layout, tile),
+                        None => self.map.remove_tile(&mut self.commands, hex),
+                    }
+                    MapEdit::Tile { hex, before, after }
+                }
+                MapEdit::Token { entity, before, after } => {
+                    let mut entity = respawned.iter()
+                        .find(|(old, _)| *old == entity)
+                        .map_or(entity, |(_, new)| *new);
+                    match (&before, after.clone()) {
+                        (Some(_), Some(combatant)) => {
+                            self.map.replace_combatant(&mut self.commands, &self.layout, entity, combatant);
+                        }
+                        (Some(_), None) => self.remove_combatant(entity),
+                        (None, Some(combatant)) => {
+                            if let Some(new) = self.add_combatant(combatant.hex(), combatant) {
+                                self.history.remap(entity, new);
+                                respawned.push((entity, new));
+                                entity = new;
+                            }
+                        }
+                        (None, None) => {}
+                    }
+                    MapEdit::Token { entity, before, after }
+                }
+                MapEdit::Season { before, after } => {
+                    self.map.season = after;
+                    MapEdit::Season { before, after }
+                }
+            };
+            applied.push(edit);
+        }
+        applied
+    }
+}
+
+pub fn undo_shortcuts(
+    keys: Res<ButtonInput<KeyCode>>,
+    ui: UIQuery,
+    mut map: MapQuery,
+) {
+    if !keys.any_pressed([KeyCode::ControlLeft, KeyCode::ControlRight]) { return; }
+    if !ui.is_admin_focused() { return; }
+    let shift = keys.any_pressed([KeyCode::ShiftLeft, KeyCode::ShiftRight]);
+    if keys.just_pressed(KeyCode::KeyY) || (shift && keys.just_pressed(KeyCode::KeyZ)) {
+        map.redo();
+    } else if keys.just_pressed(KeyCode::KeyZ) {
+        map.undo();
+    }
+}
+
+/// Strokes last as long as the mouse button is held, everything they changed
+/// is undone at once.
+pub fn commit_edits(
+    mouse_button: Res<ButtonInput<MouseButton>>,
+    mut history: ResMut<resources::EditHistory>,
+) {
+    if !mouse_button.pressed(MouseButton::Left) {
+        history.commit();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::ecs::system::RunSystemOnce;
+    use crate::map::note::Note;
+    use crate::model::id::{Id, id};
+    use crate::model::resources::TextureTreeResource;
+    use crate::model::texture_tree::TextureNode;
+    use crate::view::resources::HexLayoutResource;
+    use super::*;
+
+    fn world() -> World {
+        let mut world = World::new();
+        world.init_resource::<resources::Map>();
+        world.init_resource::<resources::Party>();
+        world.init_resource::<resources::EditHistory>();
+        world.init_resource::<HexLayoutResource>();
+        world.insert_resource(TextureTreeResource(TextureNode::branch_node()));
+        world.resource_mut::<resources::Map>().store_tile(TileDocument::new(Hex::ZERO, id!("grass", "plain", "green", "1")));
+        world
+    }
+
+    fn title(world: &World) -> Option<String> {
+        world.resource::<resources::Map>().note(Hex::ZERO).map(|note| note.title.clone())
+    }
+
+    fn set_title(world: &mut World, title: &'static str) {
+        world.run_system_once(move |mut map: MapQuery| {
+            map.set_note(Hex::ZERO, Some(Note { title: title.to_string(), ..default() }));
+            map.history.commit();
+        });
+    }
+
+    #[test]
+    fn edits_are_grouped_until_committed() {
+        let mut history = resources::EditHistory::default();
+        history.record(MapEdit::Season { before: Season::Green, after: Season::Green });
+        history.commit();
+        assert!(history.undo.is_empty());
+        history.record(MapEdit::Season { before: Season::Green, after: Season::Winter });
+        history.record(MapEdit::Season { before: Season::Winter, after: Season::Green });
+        assert!(history.undo.is_empty());
+        history.commit();
+        assert_eq!(history.undo.len(), 1);
+        assert_eq!(history.undo[0].len(), 2);
+    }
+
+    #[test]
+    fn undo_and_redo_restore_the_map() {
+        let mut world = world();
+        set_title(&mut world, "Ruins");
+        set_title(&mut world, "Tower");
+        world.run_system_once(|mut map: MapQuery| map.undo());
+        assert_eq!(title(&world).as_deref(), Some("Ruins"));
+        world.run_system_once(|mut map: MapQuery| map.undo());
+        assert_eq!(title(&world), None);
+        world.run_system_once(|mut map: MapQuery| map.redo());
+        assert_eq!(title(&world).as_deref(), Some("Ruins"));
+        world.run_system_once(|mut map: MapQuery| map.redo());
+        assert_eq!(title(&world).as_deref(), Some("Tower"));
+    }
+
+    #[test]
+    fn new_edits_drop_the_redo_stack() {
+        let mut world = world();
+        set_title(&mut world, "Ruins");
+        world.run_system_once(|mut map: MapQuery| map.undo());
+        set_title(&mut world, "Tower");
+        world.run_system_once(|mut map: MapQuery| map.redo());
+        assert_eq!(title(&world).as_deref(), Some("Tower"));
+        assert!(world.resource::<resources::EditHistory>().redo.is_empty());
+    }
+}
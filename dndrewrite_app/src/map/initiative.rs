@@ -0,0 +1,103 @@
+use bevy::prelude::*;
+use rand::Rng;
+use super::*;
+use super::resources::InitiativeEntry;
+
+impl InitiativeEntry {
+    pub fn combatant(&self) -> Entity {
+        self.combatant
+    }
+
+    pub fn total(&self) -> i16 {
+        self.total
+    }
+}
+
+impl resources::Initiative {
+    /// Sets the initiative of a combatant, keeping the current turn where it is.
+    pub fn set(&mut self, combatant: Entity, total: i16, modifier: i8) {
+        let active = self.active_combatant();
+        self.entries.retain(|entry| entry.combatant != combatant);
+        self.entries.push(InitiativeEntry {
+            combatant,
+            total,
+            modifier,
+            tie_break: rand::thread_rng().gen(),
+        });
+        self.sort(active);
+    }
+
+    /// Rolls a d20 plus the modifier and returns the total.
+    pub fn roll(&mut self, combatant: Entity, modifier: i8) -> i16 {
+        let total = rand::thread_rng().gen_range(1..=20) + modifier as i16;
+        self.set(combatant, total, modifier);
+        total
+    }
+
+    pub fn remove(&mut self, combatant: Entity) {
+        let Some(index) = self.entries.iter().position(|entry| entry.combatant == combatant) else { return; };
+        self.entries.remove(index);
+        self.active = match self.active {
+            _ if self.entries.is_empty() => None,
+            Some(active) if active > index => Some(active - 1),
+            Some(active) => Some(active % self.entries.len()),
+            None => None,
+        };
+    }
+
+    pub fn contains(&self, combatant: Entity) -> bool {
+        self.entries.iter().any(|entry| entry.combatant == combatant)
+    }
+
+    fn sort(&mut self, active: Option<Entity>) {
+        self.entries.sort_by(|a, b| b.total.cmp(&a.total)
+            .then(b.modifier.cmp(&a.modifier))
+            .then(b.tie_break.cmp(&a.tie_break)));
+        if let Some(active) = active {
+            self.active = self.entries.iter().position(|entry| entry.combatant == active);
+        }
+    }
+
+    /// Starts the encounter on its first turn, or moves to the next one.
+    pub fn next_turn(&mut self) {
+        if self.entries.is_empty() { return; }
+        self.active = match self.active {
+            None => {
+                self.round = 1;
+                Some(0)
+            }
+            Some(active) if active + 1 == self.entries.len() => {
+                self.round += 1;
+                Some(0)
+            }
+            Some(active) => Some(active + 1),
+        };
+    }
+
+    pub fn previous_turn(&mut self) {
+        let Some(active) = self.active else { return; };
+        if active == 0 {
+            if self.round <= 1 { return; }
+            self.round -= 1;
+            self.active = Some(self.entries.len() - 1);
+        } else {
+            self.active = Some(active - 1);
+        }
+    }
+
+    pub fn clear(&mut self) {
+        *self = Self::default();
+    }
+
+    pub fn entries(&self) -> &[InitiativeEntry] {
+        &self.entries
+    }
+
+    pub fn active_combatant(&self) -> Option<Entity> {
+        self.active.map(|active| self.entries[active].combatant)
+    }
+
+    pub fn round(&self) -> u32 {
+        self.round
+    }
+}
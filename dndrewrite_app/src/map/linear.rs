@@ -0,0 +1,95 @@
+use bevy::prelude::*;
+use hexx::{Hex, HexLayout};
+use crate::components::tile::MapOverlayComponent;
+use crate::map::map::overlay_z;
+use crate::map::tile::{LinearOverlay, OverlayKind};
+use crate::model::id::Id;
+use crate::model::resources::TextureTreeResource;
+use crate::view::resources::HexLayoutResource;
+use super::*;
+
+/// World space angle of every edge, in the order of the edge mask bits.
+const EDGE_ANGLES: [f32; 6] = [60., 0., -60., -120., 180., 120.];
+
+/// The mask bit of the edge `from` shares with its neighbour `to`.
+pub fn edge_bit(layout: &HexLayout, from: Hex, to: Hex) -> Option<u8> {
+    if from.unsigned_distance_to(to) != 1 { return None; }
+    let delta = layout.hex_to_world_pos(to) - layout.hex_to_world_pos(from);
+    let angle = delta.y.atan2(delta.x).to_degrees();
+    let angle_distance = |edge: f32| {
+        let difference = (angle - edge).rem_euclid(360.);
+        difference.min(360. - difference)
+    };
+    (0..EDGE_ANGLES.len())
+        .min_by(|a, b| angle_distance(EDGE_ANGLES[*a]).total_cmp(&angle_distance(EDGE_ANGLES[*b])))
+        .map(|edge| 1 << (EDGE_ANGLES.len() - 1 - edge))
+}
+
+impl resources::Map {
+    /// Replaces the linear overlay of a tile and respawns its sprites.
+    /// `family` is the texture branch holding the masks, like
+    /// `overlay/river/blue`. A mask of `0` removes the overlay.
+    pub fn set_linear(
+        &mut self,
+        commands: &mut Commands,
+        texture_tree: &TextureTreeResource,
+        layout: &HexLayoutResource,
+        hex: Hex,
+        family: Id,
+        mask: u8,
+    ) {
+        let Some(kind) = OverlayKind::from_id(&family).filter(|kind| kind.is_linear()) else {
+            warn!("'{}' is not a linear overlay.", family);
+            return;
+        };
+        self.wake(commands, texture_tree, layout, hex);
+        let Some(tile) = self.tiles.get_mut(&hex) else { return };
+        let slot = tile.overlay.linear_mut(kind);
+        if let Some(old) = slot.take() {
+            for entity in old.entities {
+                commands.entity(entity).despawn();
+            }
+        }
+        if mask == 0 { return; }
+        //The packs only draw straight pieces and bends, junctions are drawn
+        //as one dead end per connected edge
+        let pieces = match texture_tree.get(&family.extend(format!("{:06b}", mask))) {
+            Some(_) if mask.count_ones() <= 2 => vec![mask],
+            _ => (0..6).map(|bit| 1 << bit).filter(|bit| mask & bit != 0).collect(),
+        };
+        let transform = Transform::from_translation(layout.hex_to_world_pos(hex).extend(overlay_z(kind)));
+        let entities = pieces.into_iter()
+            .filter_map(|piece| texture_tree.get(&family.extend(format!("{:06b}", piece)))?.leaf())
+            .map(|leaf| commands.spawn((leaf.sprite(transform), MapOverlayComponent)).id())
+            .collect();
+        *slot = Some(LinearOverlay {
+            family,
+            mask,
+            entities,
+        });
+    }
+
+    /// Connects or disconnects two neighbouring tiles, updating both ends.
+    pub fn connect_linear(
+        &mut self,
+        commands: &mut Commands,
+        texture_tree: &TextureTreeResource,
+        layout: &HexLayoutResource,
+        family: &Id,
+        (a, b): (Hex, Hex),
+        connected: bool,
+    ) {
+        let Some(kind) = OverlayKind::from_id(family) else { return };
+        self.wake(commands, texture_tree, layout, a);
+        self.wake(commands, texture_tree, layout, b);
+        if !self.tiles.contains_key(&a) || !self.tiles.contains_key(&b) { return; }
+        for (from, to) in [(a, b), (b, a)] {
+            let Some(bit) = edge_bit(layout, from, to) else { return };
+            let current = self.tiles[&from].overlay.linear(kind);
+            let family = current.map_or_else(|| family.clone(), |linear| linear.family.clone());
+            let mask = current.map_or(0, |linear| linear.mask);
+            let mask = if connected { mask | bit } else { mask & !bit };
+            self.set_linear(commands, texture_tree, layout, from, family, mask);
+        }
+    }
+}
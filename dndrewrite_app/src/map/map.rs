@@ -1,66 +1,236 @@
 use bevy::prelude::*;
+use bevy::render::view::RenderLayers;
 use hexx::Hex;
 use crate::components::tile::{MapOverlayComponent, MapTileComponent};
-use crate::map::tile::{MapTile, Overlays};
+use crate::map::document::{MapDocument, TileDocument};
+use crate::map::fog::spawn_fog;
+use crate::map::tile::{MapTile, OverlayKind, Overlays, TileSprite, TileText};
 use crate::model::id::Id;
 use crate::model::resources::TextureTreeResource;
+use crate::view::layers::ADMIN_LAYER;
+use crate::view::resources::HexLayoutResource;
 use super::*;
 
+const TILE_Z: f32 = 0.;
+const OVERLAY_Z: f32 = 1.;
+const TEXT_Z: f32 = 2.;
+
+pub(super) fn overlay_z(kind: OverlayKind) -> f32 {
+    OVERLAY_Z + kind as usize as f32 / OverlayKind::ALL.len() as f32
+}
+
 impl resources::Map {
     pub fn place_tile(
         &mut self,
         commands: &mut Commands,
         texture_tree: &TextureTreeResource,
+        layout: &HexLayoutResource,
         hex: Hex,
         id: Id,
     ) {
-        let tile = commands.spawn((SpriteBundle {
-            texture: texture_tree.0[&id].leaf().unwrap(),
-            ..default()
-        }, MapTileComponent)).id();
-        self.tiles.entry(hex).and_modify(|map_tile| {
-            commands.entity(std::mem::replace(&mut map_tile.background, tile)).despawn();
-        }).or_insert(MapTile {
-            background: tile,
-            overlay: Overlays::default(),
-            text: None,
-        });
+        self.wake(commands, texture_tree, layout, hex);
+        let Some(id) = self.pick_variant(texture_tree, hex, id) else { return; };
+        let transform = Transform::from_translation(layout.hex_to_world_pos(hex).extend(TILE_Z));
+        let tile = commands.spawn((texture_tree.0[&id].leaf().unwrap().sprite(transform), MapTileComponent)).id();
+        let background = TileSprite { id, entity: tile };
+        match self.tiles.get_mut(&hex) {
+            Some(map_tile) => {
+                let old = std::mem::replace(&mut map_tile.background, background);
+                commands.entity(old.entity).despawn();
+            }
+            None => {
+                let fog = spawn_fog(commands, texture_tree, layout, hex);
+                self.tiles.insert(hex, MapTile {
+                    background,
+                    overlay: Overlays::default(),
+                    text: None,
+                    fog: Some(fog),
+                    note: None,
+                });
+            }
+        }
     }
 
     pub fn place_overlay(
         &mut self,
         commands: &mut Commands,
         texture_tree: &TextureTreeResource,
+        layout: &HexLayoutResource,
         hex: Hex,
         id: Id,
     ) {
+        let Some(kind) = OverlayKind::from_id(&id) else {
+            warn!("'{}' is not an overlay texture.", id);
+            return;
+        };
+        if kind.is_linear() {
+            let Some(mask) = id.last().and_then(|mask| u8::from_str_radix(mask, 2).ok()) else {
+                warn!("'{}' does not end in an edge mask.", id);
+                return;
+            };
+            self.set_linear(commands, texture_tree, layout, hex, id.init(), mask);
+            return;
+        }
+        self.wake(commands, texture_tree, layout, hex);
         let Some(overlays) = self.tiles.get_mut(&hex)
             .map(|x| &mut x.overlay) else { return };
-        let entity = commands.spawn((SpriteBundle {
-            texture: texture_tree.0[&id].leaf().unwrap(),
-            ..default()
-        }, MapOverlayComponent)).id();
-        let overlay_field = match id.get(1).unwrap() {
-            "marker" => &mut overlays.marker,
-            "flair" => &mut overlays.flair,
-            "location" => &mut overlays.location,
-            _ => panic!()
-        };
-        *overlay_field = Some(entity);
+        let z = overlay_z(kind);
+        let transform = Transform::from_translation(layout.hex_to_world_pos(hex).extend(z));
+        let mut entity = commands.spawn((texture_tree.0[&id].leaf().unwrap().sprite(transform), MapOverlayComponent));
+        if kind.is_gm_only() {
+            entity.insert(RenderLayers::layer(ADMIN_LAYER));
+        }
+        let entity = entity.id();
+        if let Some(old) = overlays.get_mut(kind).replace(TileSprite { id, entity }) {
+            commands.entity(old.entity).despawn();
+        }
     }
 
     pub fn place_text(
         &mut self,
         commands: &mut Commands,
+        layout: &HexLayoutResource,
         hex: Hex,
         text: String,
     ) {
+        if let Some(tile) = self.stored_tile_mut(hex) {
+            tile.text = Some(text);
+            return;
+        }
         let Some(text_enity) = self.tiles.get_mut(&hex)
             .map(|x| &mut x.text) else { return };
         let entity = commands.spawn(Text2dBundle {
-            text: Text::from_section(text, TextStyle::default()),
+            text: Text::from_section(text.clone(), TextStyle::default()),
+            transform: Transform::from_translation(layout.hex_to_world_pos(hex).extend(TEXT_Z)),
             ..default()
         }).id();
-        *text_enity = Some(entity);
+        if let Some(old) = text_enity.replace(TileText { text, entity }) {
+            commands.entity(old.entity).despawn();
+        }
+    }
+
+    /// Removes a tile together with everything drawn on it.
+    pub fn remove_tile(&mut self, commands: &mut Commands, hex: Hex) {
+        self.despawn_tile(commands, hex);
+        let chunk = chunk::ChunkCoord::of(hex);
+        let Some(tiles) = self.stored.get_mut(&chunk) else { return };
+        tiles.remove(&hex);
+        if tiles.is_empty() {
+            self.stored.remove(&chunk);
+        }
+    }
+
+    /// Despawns the entities of a spawned tile, a stored copy is left alone.
+    pub(super) fn despawn_tile(&mut self, commands: &mut Commands, hex: Hex) {
+        let Some(tile) = self.tiles.remove(&hex) else { return };
+        for entity in tile.entities() {
+            commands.entity(entity).despawn();
+        }
+    }
+
+    pub fn clear(&mut self, commands: &mut Commands) {
+        self.stored.clear();
+        for (_, tile) in self.tiles.drain() {
+            for entity in tile.entities() {
+                commands.entity(entity).despawn();
+            }
+        }
+    }
+
+    pub fn to_document(&self) -> MapDocument {
+        let mut tiles: Vec<TileDocument> = self.hexes()
+            .filter_map(|hex| self.tile_document(hex))
+            .collect();
+        //Keeps saved files stable between saves of the same map
+        tiles.sort_by_key(|tile| (tile.hex.y, tile.hex.x));
+        MapDocument {
+            seed: self.seed,
+            season: self.season,
+            scale: self.scale,
+            tiles,
+            ..default()
+        }
+    }
+
+    pub fn tile_document(&self, hex: Hex) -> Option<TileDocument> {
+        let Some(tile) = self.tiles.get(&hex) else {
+            return self.stored_tile(hex).cloned();
+        };
+        Some(TileDocument {
+            hex,
+            background: tile.background.id.clone(),
+            overlays: tile.overlay.ids().collect(),
+            text: tile.text.as_ref().map(|text| text.text.clone()),
+            revealed: tile.fog.is_none(),
+            note: tile.note.as_ref().map(|note| note.note.clone()),
+        })
+    }
+
+    /// Replaces the map with the document's tiles. They are only stored, the
+    /// chunks near the cameras are spawned by `chunk::stream_chunks`.
+    pub fn load_document(&mut self, commands: &mut Commands, document: MapDocument) {
+        self.clear(commands);
+        self.seed = document.seed;
+        self.season = document.season;
+        self.scale = document.scale;
+        for tile in document.tiles {
+            self.store_tile(tile);
+        }
+    }
+
+    /// Rebuilds a single tile from its document, replacing whatever was there.
+    /// Ids missing from the current texture packs are swapped for the closest
+    /// texture still available.
+    pub fn load_tile(
+        &mut self,
+        commands: &mut Commands,
+        texture_tree: &TextureTreeResource,
+        layout: &HexLayoutResource,
+        tile: TileDocument,
+    ) {
+        if self.stored.contains_key(&chunk::ChunkCoord::of(tile.hex)) {
+            self.store_tile(tile);
+            return;
+        }
+        self.remove_tile(commands, tile.hex);
+        //Generated maps name whole families, a variant is picked when placing them
+        let background = match texture_tree.get(&tile.background) {
+            Some(_) => Some(tile.background),
+            None => resolve_id(texture_tree, tile.background),
+        };
+        let Some(background) = background else { return; };
+        self.place_tile(commands, texture_tree, layout, tile.hex, background);
+        for overlay in tile.overlays {
+            let Some(overlay) = resolve_id(texture_tree, overlay) else { continue; };
+            self.place_overlay(commands, texture_tree, layout, tile.hex, overlay);
+        }
+        if let Some(text) = tile.text {
+            self.place_text(commands, layout, tile.hex, text);
+        }
+        self.set_revealed(commands, texture_tree, layout, tile.hex, tile.revealed);
+        self.set_note(commands, layout, tile.hex, tile.note);
+    }
+}
+
+fn resolve_id(texture_tree: &TextureTreeResource, id: Id) -> Option<Id> {
+    if texture_tree.get(&id).is_some_and(|node| node.leaf().is_some()) {
+        return Some(id);
+    }
+    //Junctions have no sprite of their own, only their family has to exist
+    let is_linear = OverlayKind::from_id(&id).is_some_and(OverlayKind::is_linear);
+    if is_linear && texture_tree.get(&id.init()).is_some() {
+        return Some(id);
+    }
+    let mut parent = id.init();
+    while !parent.is_empty() {
+        if let Some(rest) = texture_tree.get(&parent).and_then(|node| node.first_leaf_id()) {
+            let mut replacement = parent;
+            replacement.extend_from_slice(&rest);
+            warn!("Texture '{}' is missing, using '{}' instead.", id, replacement);
+            return Some(replacement);
+        }
+        parent = parent.init();
     }
-}
\ No newline at end of file
+    warn!("Texture '{}' is missing, skipping it.", id);
+    None
+}
@@ -0,0 +1,47 @@
+use bevy::prelude::*;
+use hexx::algorithms::a_star;
+use hexx::Hex;
+use crate::map::combatant::Combatant;
+use crate::model::id::Id;
+use crate::view::resources::HexLayoutResource;
+use super::*;
+
+/// Distance covered by one hex during combat.
+pub const FEET_PER_HEX: u32 = 5;
+
+/// Movement points needed to enter a tile with the given background, `None`
+/// if it can't be walked into.
+pub fn terrain_cost(id: &Id) -> Option<u32> {
+    let tile_type = id.get(0).unwrap_or_default();
+    let tile_variant = id.get(1).unwrap_or_default();
+    match (tile_type, tile_variant) {
+        ("ocean", _) => None,
+        ("swamp", _) | (_, "mountain") => Some(2),
+        (tile_type, "dense") if tile_type.ends_with("forest") => Some(2),
+        _ => Some(1),
+    }
+}
+
+impl resources::Map {
+    /// Cheapest path between two tiles and its length in feet.
+    pub fn find_path(&self, from: Hex, to: Hex) -> Option<(Vec<Hex>, u32)> {
+        let cost = |_, to: Hex| self.background(to).and_then(terrain_cost);
+        let path = a_star(from, to, cost)?;
+        let feet = path.iter().skip(1)
+            .filter_map(|hex| cost(*hex, *hex))
+            .sum::<u32>() * FEET_PER_HEX;
+        Some((path, feet))
+    }
+}
+
+pub fn move_combatant(
+    layout: &HexLayoutResource,
+    combatant: &mut Combatant,
+    transform: &mut Transform,
+    hex: Hex,
+) {
+    combatant.set_hex(hex);
+    let position = layout.hex_to_world_pos(hex);
+    transform.translation.x = position.x;
+    transform.translation.y = position.y;
+}
@@ -0,0 +1,122 @@
+use bevy::prelude::*;
+use bevy::render::view::RenderLayers;
+use hexx::Hex;
+use serde::{Deserialize, Serialize};
+use crate::map::tile::TileNote;
+use crate::view::layers::ADMIN_LAYER;
+use crate::view::resources::HexLayoutResource;
+use super::*;
+
+/// Note titles sit below the tile's own label, above the tokens.
+const NOTE_Z: f32 = 2.2;
+const NOTE_OFFSET: f32 = -60.;
+const NOTE_COLOR: Color = Color::srgb(1., 0.9, 0.6);
+
+#[derive(Serialize, Deserialize, Debug, Copy, Clone, Eq, PartialEq, Default)]
+pub enum NoteVisibility {
+    #[default]
+    GmOnly,
+    Players,
+}
+
+impl NoteVisibility {
+    pub fn name(self) -> &'static str {
+        match self {
+            NoteVisibility::GmOnly => "GM only",
+            NoteVisibility::Players => "Players",
+        }
+    }
+
+    pub fn toggled(self) -> Self {
+        match self {
+            NoteVisibility::GmOnly => NoteVisibility::Players,
+            NoteVisibility::Players => NoteVisibility::GmOnly,
+        }
+    }
+}
+
+/// A hex crawl key entry, its body is markdown.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Default)]
+pub struct Note {
+    pub title: String,
+    #[serde(default)]
+    pub body: String,
+    #[serde(default)]
+    pub tags: Vec<String>,
+    #[serde(default)]
+    pub visibility: NoteVisibility,
+}
+
+impl Note {
+    pub fn is_empty(&self) -> bool {
+        self.title.is_empty() && self.body.is_empty() && self.tags.is_empty()
+    }
+
+    /// Case insensitive search over the title, body and tags.
+    pub fn matches(&self, query: &str) -> bool {
+        let query = query.to_lowercase();
+        std::iter::once(&self.title)
+            .chain(std::iter::once(&self.body))
+            .chain(&self.tags)
+            .any(|text| text.to_lowercase().contains(&query))
+    }
+}
+
+impl resources::Map {
+    pub fn has_tile(&self, hex: Hex) -> bool {
+        self.tiles.contains_key(&hex) || self.stored_tile(hex).is_some()
+    }
+
+    pub fn note(&self, hex: Hex) -> Option<&Note> {
+        match self.tiles.get(&hex) {
+            Some(tile) => tile.note.as_ref().map(|note| &note.note),
+            None => self.stored_tile(hex)?.note.as_ref(),
+        }
+    }
+
+    /// Replaces the note of a tile, `None` removes it. The title is shown on
+    /// the map, to the players too if the note is visible to them.
+    pub fn set_note(
+        &mut self,
+        commands: &mut Commands,
+        layout: &HexLayoutResource,
+        hex: Hex,
+        note: Option<Note>,
+    ) {
+        if let Some(tile) = self.stored_tile_mut(hex) {
+            tile.note = note.filter(|note| !note.is_empty());
+            return;
+        }
+        let Some(tile) = self.tiles.get_mut(&hex) else { return };
+        if let Some(old) = tile.note.take() {
+            commands.entity(old.entity).despawn();
+        }
+        let Some(note) = note.filter(|note| !note.is_empty()) else { return };
+        let position = layout.hex_to_world_pos(hex) + Vec2::new(0., NOTE_OFFSET);
+        let mut label = commands.spawn(Text2dBundle {
+            text: Text::from_section(note.title.clone(), TextStyle {
+                font_size: 28.,
+                color: NOTE_COLOR,
+                ..default()
+            }),
+            transform: Transform::from_translation(position.extend(NOTE_Z)),
+            ..default()
+        });
+        if note.visibility == NoteVisibility::GmOnly {
+            label.insert(RenderLayers::layer(ADMIN_LAYER));
+        }
+        let entity = label.id();
+        tile.note = Some(TileNote { note, entity });
+    }
+
+    /// Every note matching the query, ordered by title.
+    pub fn search_notes(&self, query: &str) -> Vec<(Hex, &Note)> {
+        let mut hits: Vec<(Hex, &Note)> = self.tiles.iter()
+            .filter_map(|(hex, tile)| Some((*hex, &tile.note.as_ref()?.note)))
+            .chain(self.stored_tiles().filter_map(|tile| Some((tile.hex, tile.note.as_ref()?))))
+            .filter(|(_, note)| note.matches(query))
+            .collect();
+        hits.sort_by(|(a_hex, a), (b_hex, b)| a.title.cmp(&b.title).then((a_hex.y, a_hex.x).cmp(&(b_hex.y, b_hex.x))));
+        hits
+    }
+}
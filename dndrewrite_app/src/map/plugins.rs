@@ -0,0 +1,34 @@
+use bevy::prelude::*;
+use crate::app::notes_panel;
+use crate::app::resources::AppLoaded;
+use super::*;
+
+pub struct MapPlugin;
+
+impl Plugin for MapPlugin {
+    fn build(&self, app: &mut App) {
+        app
+            .init_resource::<resources::Map>()
+            .init_resource::<resources::Party>()
+            .init_resource::<resources::Initiative>()
+            .init_resource::<resources::EditHistory>()
+            .add_event::<events::SaveMap>()
+            .add_event::<events::LoadMap>()
+            .add_event::<events::GenerateMap>()
+            .add_systems(Update, (
+                saving::save_load_shortcuts,
+                saving::save_map,
+                saving::load_map,
+                generator::generate_shortcut.run_if(notes_panel::not_typing),
+                generator::generate_map,
+                history::undo_shortcuts.run_if(notes_panel::not_typing),
+            )
+                .chain()
+                .run_if(resource_exists::<AppLoaded>))
+            .add_systems(Update, (chunk::refresh_textures, chunk::stream_chunks)
+                .chain()
+                .run_if(resource_exists::<AppLoaded>))
+            .add_systems(PostUpdate, history::commit_edits
+                .run_if(resource_exists::<AppLoaded>));
+    }
+}
@@ -0,0 +1,114 @@
+use bevy::ecs::system::SystemParam;
+use bevy::prelude::*;
+use bevy::utils::HashSet;
+use hexx::Hex;
+use crate::map::combatant::{Combatant, CombatantType};
+use crate::model::id::Id;
+use crate::model::resources::TextureTreeResource;
+use crate::view::resources::HexLayoutResource;
+use super::*;
+
+/// Everything needed to edit the map from a system.
+#[derive(SystemParam)]
+pub struct MapQuery<'w, 's> {
+    pub map: ResMut<'w, resources::Map>,
+    pub texture_tree: Res<'w, TextureTreeResource>,
+    pub layout: Res<'w, HexLayoutResource>,
+    pub party: ResMut<'w, resources::Party>,
+    pub history: ResMut<'w, resources::EditHistory>,
+    pub commands: Commands<'w, 's>,
+}
+
+/// Every edit made through here is recorded in the [`resources::EditHistory`].
+impl<'w, 's> MapQuery<'w, 's> {
+    pub fn place_tile(&mut self, hex: Hex, id: Id) {
+        self.edit_tiles(&[hex], |query| query.map.place_tile(&mut query.commands, &query.texture_tree, &query.layout, hex, id));
+    }
+
+    pub fn place_overlay(&mut self, hex: Hex, id: Id) {
+        self.edit_tiles(&[hex], |query| query.map.place_overlay(&mut query.commands, &query.texture_tree, &query.layout, hex, id));
+    }
+
+    pub fn connect_linear(&mut self, family: &Id, hexes: (Hex, Hex), connected: bool) {
+        self.edit_tiles(&[hexes.0, hexes.1], |query| {
+            query.map.connect_linear(&mut query.commands, &query.texture_tree, &query.layout, family, hexes, connected);
+        });
+    }
+
+    pub fn set_revealed(&mut self, hex: Hex, revealed: bool) {
+        self.edit_tiles(&[hex], |query| query.map.set_revealed(&mut query.commands, &query.texture_tree, &query.layout, hex, revealed));
+    }
+
+    pub fn spawn_combatant(&mut self, hex: Hex, combatant: Combatant) -> Option<Entity> {
+        let entity = self.add_combatant(hex, combatant.clone())?;
+        let mut after = combatant;
+        after.set_hex(hex);
+        self.history.record(history::MapEdit::Token { entity, before: None, after: Some(after) });
+        Some(entity)
+    }
+
+    /// `combatant` is the token's current state, kept so the removal can be undone.
+    pub fn despawn_combatant(&mut self, entity: Entity, combatant: Combatant) {
+        self.remove_combatant(entity);
+        self.history.record(history::MapEdit::Token { entity, before: Some(combatant), after: None });
+    }
+
+    pub fn set_note(&mut self, hex: Hex, note: Option<note::Note>) {
+        self.edit_tiles(&[hex], |query| query.map.set_note(&mut query.commands, &query.layout, hex, note));
+    }
+
+    pub fn set_season(&mut self, season: season::Season) {
+        let before = self.map.season();
+        self.history.record(history::MapEdit::Season { before, after: season });
+        //Only the tiles that change are kept for undo, maps can be huge
+        let changes = self.map.season_changes(&self.texture_tree, season);
+        let hexes: Vec<Hex> = changes.iter().map(|(hex, _)| *hex).collect();
+        self.edit_tiles(&hexes, |query| query.map.set_season(&mut query.commands, &query.texture_tree, &query.layout, season, changes));
+    }
+
+    /// Swaps every tile for the document's as one undoable edit. Tokens stay
+    /// where they are.
+    pub fn replace_tiles(&mut self, document: document::MapDocument) {
+        //Only the tiles that change are kept for undo, maps can be huge
+        let replaced: HashSet<Hex> = document.tiles.iter().map(|tile| tile.hex).collect();
+        let hexes: Vec<Hex> = self.map.hexes()
+            .filter(|hex| !replaced.contains(hex))
+            .chain(document.tiles.iter()
+                .filter(|tile| self.map.tile_document(tile.hex).as_ref() != Some(*tile))
+                .map(|tile| tile.hex))
+            .collect();
+        //A group of its own, not merged into a stroke in progress
+        self.history.commit();
+        self.history.record(history::MapEdit::Season { before: self.map.season(), after: document.season });
+        self.edit_tiles(&hexes, |query| query.map.load_document(&mut query.commands, document));
+        self.history.commit();
+    }
+
+    /// Records a change made directly to a token's components, like a move.
+    pub fn record_token(&mut self, entity: Entity, before: Combatant, after: Combatant) {
+        self.history.record(history::MapEdit::Token { entity, before: Some(before), after: Some(after) });
+    }
+
+    fn edit_tiles(&mut self, hexes: &[Hex], edit: impl FnOnce(&mut Self)) {
+        let before: Vec<_> = hexes.iter().map(|hex| self.map.tile_document(*hex)).collect();
+        edit(self);
+        for (hex, before) in hexes.iter().zip(before) {
+            let after = self.map.tile_document(*hex);
+            self.history.record(history::MapEdit::Tile { hex: *hex, before, after });
+        }
+    }
+
+    pub(super) fn add_combatant(&mut self, hex: Hex, combatant: Combatant) -> Option<Entity> {
+        let combatant_type = combatant.combatant_type();
+        let entity = self.map.spawn_combatant(&mut self.commands, &self.texture_tree, &self.layout, hex, combatant)?;
+        if combatant_type == CombatantType::Player {
+            self.party.push(entity);
+        }
+        Some(entity)
+    }
+
+    pub(super) fn remove_combatant(&mut self, entity: Entity) {
+        self.party.retain(|member| *member != entity);
+        self.map.despawn_combatant(&mut self.commands, entity);
+    }
+}
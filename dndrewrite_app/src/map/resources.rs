@@ -1,3 +1,4 @@
+use std::path::PathBuf;
 use bevy::prelude::{Deref, DerefMut, Entity, Resource};
 use bevy::utils::HashMap;
 use hexx::Hex;
@@ -8,6 +9,44 @@ pub struct Party(pub(super) Vec<Entity>);
 
 #[derive(Resource, Default, Debug)]
 pub struct Map {
+    /// Tiles of the chunks near a camera, with their entities.
     pub(super) tiles: HashMap<Hex, tile::MapTile>,
+    /// Every other chunk, kept without entities until a camera comes close.
+    pub(super) stored: HashMap<chunk::ChunkCoord, HashMap<Hex, document::TileDocument>>,
     pub(super) combatants: Vec<Entity>,
-}
\ No newline at end of file
+    /// Picks the variants of texture families, saved so repainting is reproducible.
+    pub(super) seed: u64,
+    pub(super) season: season::Season,
+    pub(super) scale: scale::MapScale,
+}
+
+#[derive(Debug, Clone)]
+pub struct InitiativeEntry {
+    pub(super) combatant: Entity,
+    pub(super) total: i16,
+    pub(super) modifier: i8,
+    /// Rolled once per entry, settles ties the modifier doesn't.
+    pub(super) tie_break: u32,
+}
+
+/// Turn order of the current encounter, highest initiative first.
+#[derive(Resource, Default, Debug)]
+pub struct Initiative {
+    pub(super) entries: Vec<InitiativeEntry>,
+    pub(super) active: Option<usize>,
+    pub(super) round: u32,
+}
+
+/// The file the map is saved to and loaded from.
+#[derive(Resource, Debug, Deref, DerefMut)]
+pub struct MapPath(pub(super) PathBuf);
+
+/// Undo and redo stacks of map edits. Every entry is one group, usually
+/// everything a single brush stroke changed.
+#[derive(Resource, Default, Debug)]
+pub struct EditHistory {
+    pub(super) undo: Vec<Vec<history::MapEdit>>,
+    pub(super) redo: Vec<Vec<history::MapEdit>>,
+    /// Edits of the stroke still in progress.
+    pub(super) pending: Vec<history::MapEdit>,
+}
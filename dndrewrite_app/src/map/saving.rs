@@ -0,0 +1,79 @@
+use std::path::PathBuf;
+use bevy::prelude::*;
+use crate::model::settings::{SETTINGS_FILE, Settings};
+use super::*;
+
+/// Map file to save to and load from.
+pub const MAP_ENV_VAR: &str = "DNDREWRITE_MAP";
+/// Map file to save to and load from, overrides the environment variable.
+pub const MAP_ARG: &str = "--map";
+/// Used when no map file is configured, next to the settings file.
+const DEFAULT_MAP_FILE: &str = "maps/map.ron";
+
+impl resources::MapPath {
+    /// The `--map` argument, then the environment variable, then the
+    /// settings file.
+    pub fn resolve(args: &[String]) -> Self {
+        //The last one wins
+        let arg = args.windows(2).rev()
+            .find(|pair| pair[0] == MAP_ARG)
+            .map(|pair| PathBuf::from(&pair[1]));
+        let settings = || match Settings::read() {
+            Ok(settings) => settings.map_file,
+            Err(err) => {
+                warn!("Using the default map file, {} can't be read: {}", SETTINGS_FILE, err);
+                None
+            }
+        };
+        let path = arg
+            .or_else(|| std::env::var_os(MAP_ENV_VAR).map(PathBuf::from))
+            .or_else(settings)
+            .unwrap_or_else(|| PathBuf::from(DEFAULT_MAP_FILE));
+        Self(path)
+    }
+}
+
+pub fn save_load_shortcuts(
+    keys: Res<ButtonInput<KeyCode>>,
+    map_path: Res<resources::MapPath>,
+    mut save_events: EventWriter<events::SaveMap>,
+    mut load_events: EventWriter<events::LoadMap>,
+) {
+    if !keys.any_pressed([KeyCode::ControlLeft, KeyCode::ControlRight]) { return; }
+    if keys.just_pressed(KeyCode::KeyS) {
+        save_events.send(events::SaveMap(map_path.0.clone()));
+    }
+    if keys.just_pressed(KeyCode::KeyO) {
+        load_events.send(events::LoadMap(map_path.0.clone()));
+    }
+}
+
+pub fn save_map(
+    map: Res<resources::Map>,
+    mut save_events: EventReader<events::SaveMap>,
+) {
+    for events::SaveMap(path) in save_events.read() {
+        match map.to_document().write(path) {
+            Ok(()) => info!("Saved map to '{}'.", path.display()),
+            Err(err) => error!("Failed to save map to '{}': {:?}", path.display(), err),
+        }
+    }
+}
+
+pub fn load_map(
+    mut map: ResMut<resources::Map>,
+    mut history: ResMut<resources::EditHistory>,
+    mut load_events: EventReader<events::LoadMap>,
+    mut commands: Commands,
+) {
+    for events::LoadMap(path) in load_events.read() {
+        match document::MapDocument::read(path) {
+            Ok(document) => {
+                map.load_document(&mut commands, document);
+                history.clear();
+                info!("Loaded map from '{}'.", path.display());
+            }
+            Err(err) => error!("Failed to load map from '{}': {:?}", path.display(), err),
+        }
+    }
+}
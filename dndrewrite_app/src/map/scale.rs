@@ -0,0 +1,63 @@
+use serde::{Deserialize, Serialize};
+use super::*;
+
+const FEET_PER_MILE: f32 = 5280.;
+
+/// How far it is across one hex of the map, in miles.
+#[derive(Serialize, Deserialize, Debug, Copy, Clone, PartialEq)]
+#[serde(transparent)]
+pub struct MapScale(pub f32);
+
+impl MapScale {
+    /// Common hex sizes, from combat maps to continents.
+    pub const PRESETS: [MapScale; 6] = [
+        MapScale(5. / FEET_PER_MILE),
+        MapScale(1.),
+        MapScale(3.),
+        MapScale(6.),
+        MapScale(12.),
+        MapScale(24.),
+    ];
+
+    pub fn miles(self, hexes: u32) -> f32 {
+        self.0 * hexes as f32
+    }
+
+    pub fn feet(self, hexes: u32) -> f32 {
+        self.miles(hexes) * FEET_PER_MILE
+    }
+
+    /// The preset after this one, custom scales start over from the smallest.
+    pub fn next(self) -> Self {
+        Self::PRESETS.iter()
+            .copied()
+            .find(|preset| preset.0 > self.0 + f32::EPSILON)
+            .unwrap_or(Self::PRESETS[0])
+    }
+
+    /// Writes a distance in miles, or in feet when it's under a mile.
+    pub fn describe(self, hexes: u32) -> String {
+        let miles = self.miles(hexes);
+        if miles < 1. {
+            format!("{:.0} ft", self.feet(hexes))
+        } else {
+            format!("{:.1} mi ({:.0} ft)", miles, self.feet(hexes))
+        }
+    }
+}
+
+impl Default for MapScale {
+    fn default() -> Self {
+        MapScale(6.)
+    }
+}
+
+impl resources::Map {
+    pub fn scale(&self) -> MapScale {
+        self.scale
+    }
+
+    pub fn set_scale(&mut self, scale: MapScale) {
+        self.scale = scale;
+    }
+}
@@ -0,0 +1,130 @@
+use bevy::prelude::*;
+use hexx::Hex;
+use serde::{Deserialize, Serialize};
+use crate::map::tile::OverlayKind;
+use crate::model::id::Id;
+use crate::model::resources::TextureTreeResource;
+use crate::view::resources::HexLayoutResource;
+use super::*;
+
+/// Position of the colour in tile ids, `[type, variant, color, number]`.
+const COLOR_INDEX: usize = 2;
+
+#[derive(Serialize, Deserialize, Debug, Copy, Clone, Eq, PartialEq, Default)]
+pub enum Season {
+    #[default]
+    Green,
+    Winter,
+}
+
+impl Season {
+    pub const ALL: [Season; 2] = [Season::Green, Season::Winter];
+
+    /// The colour branch tiles of this season are found under.
+    pub fn color(self) -> &'static str {
+        match self {
+            Season::Green => "green",
+            Season::Winter => "winter",
+        }
+    }
+
+    pub fn next(self) -> Self {
+        Self::ALL[(self as usize + 1) % Self::ALL.len()]
+    }
+}
+
+impl resources::Map {
+    pub fn season(&self) -> Season {
+        self.season
+    }
+
+    /// The counterpart of a tile texture or family in the map's season.
+    pub fn in_season(&self, texture_tree: &TextureTreeResource, id: Id) -> Id {
+        counterpart(texture_tree, id, self.season)
+    }
+
+    /// The backgrounds that change in `season`, tiles without a counterpart
+    /// in it stay as they are.
+    pub fn season_changes(&self, texture_tree: &TextureTreeResource, season: Season) -> Vec<(Hex, Id)> {
+        self.hexes()
+            .filter_map(|hex| {
+                let background = self.background(hex)?;
+                //Generated maps store families, the variant they show keeps its number
+                let shown = match texture_tree.get(background) {
+                    Some(node) if node.leaf().is_none() => self.pick_variant(texture_tree, hex, background.clone()),
+                    _ => None,
+                }.unwrap_or_else(|| background.clone());
+                let seasonal = counterpart(texture_tree, shown.clone(), season);
+                (seasonal != shown).then_some((hex, seasonal))
+            })
+            .collect()
+    }
+
+    /// Switches the season and repaints the tiles `season_changes` found.
+    pub fn set_season(
+        &mut self,
+        commands: &mut Commands,
+        texture_tree: &TextureTreeResource,
+        layout: &HexLayoutResource,
+        season: Season,
+        changes: Vec<(Hex, Id)>,
+    ) {
+        self.season = season;
+        let changed = changes.len();
+        for (hex, id) in changes {
+            //Stored chunks are repainted once they're spawned again
+            match self.stored_tile_mut(hex) {
+                Some(tile) => tile.background = id,
+                None => self.place_tile(commands, texture_tree, layout, hex, id),
+            }
+        }
+        info!("Switched to {:?}, repainted {} tiles.", season, changed);
+    }
+}
+
+/// The counterpart of a tile texture or family in `season`. Tiles without
+/// one, like the yellow deserts, stay as they are.
+fn counterpart(texture_tree: &TextureTreeResource, id: Id, season: Season) -> Id {
+    if OverlayKind::from_id(&id).is_some() { return id; }
+    let Some(color) = id.get(COLOR_INDEX) else { return id; };
+    let target = season.color();
+    if color == target || !Season::ALL.iter().any(|season| season.color() == color) {
+        return id;
+    }
+    let mut seasonal = id.clone();
+    seasonal[COLOR_INDEX] = target.to_string();
+    match texture_tree.get(&seasonal) {
+        Some(_) => seasonal,
+        None => id,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::ecs::world::CommandQueue;
+    use bevy::utils::HashMap;
+    use crate::map::document::TileDocument;
+    use crate::model::id::id;
+    use crate::model::texture_tree::{TextureLeaf, TextureNode};
+    use super::*;
+
+    #[test]
+    fn stored_families_keep_their_variant() {
+        let world = World::new();
+        let mut queue = CommandQueue::default();
+        let mut commands = Commands::new(&mut queue, &world);
+        let leaves = ["green", "winter"].into_iter()
+            .flat_map(|color| (1..=8).map(move |number| (id!("grass", "plain", color, &number.to_string()), TextureLeaf::default())));
+        let texture_tree = TextureTreeResource(TextureNode::new(HashMap::from_iter(leaves)));
+        let hex = Hex::new(3, -2);
+        let mut map = resources::Map::default();
+        map.store_tile(TileDocument::new(hex, id!("grass", "plain", "green")));
+        let shown = map.pick_variant(&texture_tree, hex, id!("grass", "plain", "green")).unwrap();
+
+        let changes = map.season_changes(&texture_tree, Season::Winter);
+        map.set_season(&mut commands, &texture_tree, &HexLayoutResource::default(), Season::Winter, changes);
+        let background = &map.stored_tile(hex).unwrap().background;
+        assert_eq!(background.get(COLOR_INDEX), Some("winter"));
+        assert_eq!(background.last(), shown.last());
+    }
+}
@@ -1,4 +1,6 @@
 use bevy::prelude::Entity;
+use crate::model::id::Id;
+use super::*;
 
 pub enum TilePart {
     Background,
@@ -6,16 +8,177 @@ pub enum TilePart {
     Text,
 }
 
+/// The overlay slots of a tile, in the order they are drawn.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum OverlayKind {
+    River,
+    Road,
+    Path,
+    Location,
+    Flair,
+    Figure,
+    Marker,
+}
+
+impl OverlayKind {
+    pub const ALL: [OverlayKind; 7] = [
+        OverlayKind::River,
+        OverlayKind::Road,
+        OverlayKind::Path,
+        OverlayKind::Location,
+        OverlayKind::Flair,
+        OverlayKind::Figure,
+        OverlayKind::Marker,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            OverlayKind::River => "river",
+            OverlayKind::Road => "road",
+            OverlayKind::Path => "path",
+            OverlayKind::Location => "location",
+            OverlayKind::Flair => "flair",
+            OverlayKind::Figure => "figure",
+            OverlayKind::Marker => "marker",
+        }
+    }
+
+    /// Linear overlays connect to their neighbours, their ids end in an edge
+    /// mask instead of a number.
+    pub fn is_linear(self) -> bool {
+        matches!(self, OverlayKind::River | OverlayKind::Road | OverlayKind::Path)
+    }
+
+    /// Hidden from the players' window.
+    pub fn is_gm_only(self) -> bool {
+        matches!(self, OverlayKind::Marker)
+    }
+
+    /// Overlay ids look like `overlay/<kind>/<style>/<number>`.
+    /// Whether `id` is anywhere in the overlay textures, branches included.
+    pub fn is_overlay(id: &Id) -> bool {
+        id.get(0) == Some("overlay")
+    }
+
+    pub fn from_id(id: &Id) -> Option<Self> {
+        if !Self::is_overlay(id) { return None; }
+        let name = id.get(1)?;
+        Self::ALL.into_iter().find(|kind| kind.name() == name)
+    }
+}
+
+#[derive(Debug)]
+pub struct TileSprite {
+    pub(super) id: Id,
+    pub(super) entity: Entity,
+}
+
+#[derive(Debug)]
+pub struct TileText {
+    pub(super) text: String,
+    pub(super) entity: Entity,
+}
+
+/// A river, road or path running through a tile. Bit `5 - n` of the mask is
+/// set when the feature leaves through edge `n`, counted clockwise from the
+/// north-east edge, which is the order of the `c` masks in the file names.
+#[derive(Debug)]
+pub struct LinearOverlay {
+    pub(super) family: Id,
+    pub(super) mask: u8,
+    pub(super) entities: Vec<Entity>,
+}
+
+impl LinearOverlay {
+    pub fn id(&self) -> Id {
+        self.family.extend(format!("{:06b}", self.mask))
+    }
+}
+
 #[derive(Debug, Default)]
 pub struct Overlays {
-    pub(super) location: Option<Entity>,
-    pub(super) flair: Option<Entity>,
-    pub(super) marker: Option<Entity>,
+    pub(super) river: Option<LinearOverlay>,
+    pub(super) road: Option<LinearOverlay>,
+    pub(super) path: Option<LinearOverlay>,
+    pub(super) location: Option<TileSprite>,
+    pub(super) flair: Option<TileSprite>,
+    pub(super) figure: Option<TileSprite>,
+    pub(super) marker: Option<TileSprite>,
+}
+
+impl Overlays {
+    pub fn get_mut(&mut self, kind: OverlayKind) -> &mut Option<TileSprite> {
+        match kind {
+            OverlayKind::Location => &mut self.location,
+            OverlayKind::Flair => &mut self.flair,
+            OverlayKind::Figure => &mut self.figure,
+            OverlayKind::Marker => &mut self.marker,
+            _ => panic!("'{}' overlays are linear", kind.name()),
+        }
+    }
+
+    pub fn linear(&self, kind: OverlayKind) -> Option<&LinearOverlay> {
+        match kind {
+            OverlayKind::River => self.river.as_ref(),
+            OverlayKind::Road => self.road.as_ref(),
+            OverlayKind::Path => self.path.as_ref(),
+            _ => None,
+        }
+    }
+
+    pub fn linear_mut(&mut self, kind: OverlayKind) -> &mut Option<LinearOverlay> {
+        match kind {
+            OverlayKind::River => &mut self.river,
+            OverlayKind::Road => &mut self.road,
+            OverlayKind::Path => &mut self.path,
+            _ => panic!("'{}' overlays are not linear", kind.name()),
+        }
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &TileSprite> {
+        [
+            &self.location,
+            &self.flair,
+            &self.figure,
+            &self.marker,
+        ].into_iter().flatten()
+    }
+
+    pub fn iter_linear(&self) -> impl Iterator<Item = &LinearOverlay> {
+        [&self.river, &self.road, &self.path].into_iter().flatten()
+    }
+
+    /// Ids of every overlay on the tile, linear ones carrying their full mask.
+    pub fn ids(&self) -> impl Iterator<Item = Id> + '_ {
+        self.iter_linear().map(LinearOverlay::id)
+            .chain(self.iter().map(|overlay| overlay.id.clone()))
+    }
+}
+
+#[derive(Debug)]
+pub struct TileNote {
+    pub(super) note: note::Note,
+    /// The title shown on the map.
+    pub(super) entity: Entity,
 }
 
 #[derive(Debug)]
 pub struct MapTile {
-    pub(super) background: Entity,
+    pub(super) background: TileSprite,
     pub(super) overlay: Overlays,
-    pub(super) text: Option<Entity>,
-}
\ No newline at end of file
+    pub(super) text: Option<TileText>,
+    /// The clouds covering the tile in the players' window, `None` once revealed.
+    pub(super) fog: Option<Entity>,
+    pub(super) note: Option<TileNote>,
+}
+
+impl MapTile {
+    pub fn entities(&self) -> impl Iterator<Item = Entity> + '_ {
+        std::iter::once(self.background.entity)
+            .chain(self.overlay.iter().map(|overlay| overlay.entity))
+            .chain(self.overlay.iter_linear().flat_map(|linear| linear.entities.iter().copied()))
+            .chain(self.text.iter().map(|text| text.entity))
+            .chain(self.fog)
+            .chain(self.note.iter().map(|note| note.entity))
+    }
+}
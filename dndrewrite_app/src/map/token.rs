@@ -0,0 +1,54 @@
+use bevy::prelude::*;
+use hexx::Hex;
+use crate::map::combatant::Combatant;
+use crate::model::resources::TextureTreeResource;
+use crate::view::resources::HexLayoutResource;
+use super::*;
+
+/// Tokens stand above the tiles and their labels, but below the fog.
+const TOKEN_Z: f32 = 2.5;
+
+fn token_transform(layout: &HexLayoutResource, combatant: &Combatant) -> Transform {
+    Transform::from_translation(layout.hex_to_world_pos(combatant.hex()).extend(TOKEN_Z))
+        .with_scale(Vec3::splat(combatant.size().scale()))
+}
+
+impl resources::Map {
+    pub fn spawn_combatant(
+        &mut self,
+        commands: &mut Commands,
+        texture_tree: &TextureTreeResource,
+        layout: &HexLayoutResource,
+        hex: Hex,
+        mut combatant: Combatant,
+    ) -> Option<Entity> {
+        let Some(leaf) = texture_tree.get(combatant.texture()).and_then(|node| node.leaf()) else {
+            warn!("Figure '{}' does not exist.", combatant.texture());
+            return None;
+        };
+        combatant.set_hex(hex);
+        let transform = token_transform(layout, &combatant);
+        info!("Spawned '{}' at {:?}.", combatant.name(), hex);
+        let entity = commands.spawn((leaf.sprite(transform), combatant)).id();
+        self.combatants.push(entity);
+        Some(entity)
+    }
+
+    /// Overwrites a token that is already on the map, moving it to the
+    /// combatant's hex.
+    pub fn replace_combatant(
+        &mut self,
+        commands: &mut Commands,
+        layout: &HexLayoutResource,
+        entity: Entity,
+        combatant: Combatant,
+    ) {
+        let Some(mut token) = commands.get_entity(entity) else { return; };
+        token.insert((token_transform(layout, &combatant), combatant));
+    }
+
+    pub fn despawn_combatant(&mut self, commands: &mut Commands, entity: Entity) {
+        self.combatants.retain(|combatant| *combatant != entity);
+        commands.entity(entity).despawn_recursive();
+    }
+}
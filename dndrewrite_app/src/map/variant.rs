@@ -0,0 +1,45 @@
+use bevy::prelude::*;
+use hexx::Hex;
+use crate::model::id::Id;
+use crate::model::resources::TextureTreeResource;
+use super::*;
+
+/// SplitMix64 finalizer, spreads neighbouring inputs over the whole range.
+pub(super) fn mix(mut value: u64) -> u64 {
+    value = (value ^ (value >> 30)).wrapping_mul(0xbf58476d1ce4e5b9);
+    value = (value ^ (value >> 27)).wrapping_mul(0x94d049bb133111eb);
+    value ^ (value >> 31)
+}
+
+/// Same seed, hex and family always hash to the same value, on every machine.
+fn variant_hash(seed: u64, hex: Hex, family: &Id) -> u64 {
+    let family = family.iter()
+        .flat_map(|name| name.bytes().chain([b'/']))
+        .fold(0xcbf29ce484222325u64, |hash, byte| (hash ^ byte as u64).wrapping_mul(0x100000001b3));
+    [hex.x as u32 as u64, hex.y as u32 as u64, family]
+        .into_iter()
+        .fold(mix(seed), |hash, value| mix(hash ^ value))
+}
+
+impl resources::Map {
+    /// Leaves are returned as they are, families like `grassland/dense/green`
+    /// become one of the leaves below them, chosen by the map's seed and the hex.
+    pub fn pick_variant(&self, texture_tree: &TextureTreeResource, hex: Hex, id: Id) -> Option<Id> {
+        let Some(node) = texture_tree.get(&id) else {
+            warn!("Texture '{}' does not exist.", id);
+            return None;
+        };
+        if node.leaf().is_some() {
+            return Some(id);
+        }
+        let variants = node.leaf_ids();
+        if variants.is_empty() {
+            warn!("'{}' has no textures to pick from.", id);
+            return None;
+        }
+        let index = variant_hash(self.seed, hex, &id) % variants.len() as u64;
+        let mut variant = id;
+        variant.extend_from_slice(&variants[index as usize]);
+        Some(variant)
+    }
+}
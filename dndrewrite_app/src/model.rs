@@ -4,4 +4,11 @@ pub mod load_handler;
 pub mod errors;
 pub mod id;
 pub mod texture_tree;
+pub mod atlas;
+pub mod watcher;
+pub mod events;
+pub mod report;
+pub mod roots;
+pub mod settings;
+pub mod manifest;
 pub mod plugins;
@@ -0,0 +1,81 @@
+use bevy::prelude::*;
+use bevy::utils::HashMap;
+use super::*;
+use super::texture_tree::TextureLeaf;
+
+/// Largest atlas page, room for 81 of the 420px tiles while staying within
+/// what every GPU we care about supports.
+const ATLAS_MAX_SIZE: u32 = 4096;
+/// Transparent gap between packed textures so filtering doesn't bleed.
+const ATLAS_PADDING: u32 = 2;
+
+/// Packs the loaded textures into atlases, one per family (the branch the
+/// leaves hang off), so sprites of the same family batch into one draw.
+/// The source images are freed once their handles are dropped.
+pub fn bake(
+    textures: HashMap<id::Id, Handle<Image>>,
+    images: &mut Assets<Image>,
+    layouts: &mut Assets<TextureAtlasLayout>,
+) -> HashMap<id::Id, TextureLeaf> {
+    let mut families: HashMap<id::Id, Vec<(id::Id, Handle<Image>)>> = HashMap::new();
+    for (id, handle) in textures {
+        families.entry(id.init()).or_default().push((id, handle));
+    }
+    let mut leaves = HashMap::new();
+    let mut pages = 0;
+    for (family, mut members) in families {
+        //Sorted so a texture keeps its atlas index between runs
+        members.sort_by(|a, b| a.0.cmp(&b.0));
+        pages += bake_family(&family, &members, images, layouts, &mut leaves);
+    }
+    info!("Baked {} textures into {} atlas pages.", leaves.len(), pages);
+    leaves
+}
+
+/// Bakes one atlas page, halving the family until every half fits.
+/// Returns the number of pages made.
+fn bake_family(
+    family: &id::Id,
+    members: &[(id::Id, Handle<Image>)],
+    images: &mut Assets<Image>,
+    layouts: &mut Assets<TextureAtlasLayout>,
+    leaves: &mut HashMap<id::Id, TextureLeaf>,
+) -> usize {
+    let mut builder = TextureAtlasBuilder::default();
+    builder
+        .max_size(UVec2::splat(ATLAS_MAX_SIZE))
+        .padding(UVec2::splat(ATLAS_PADDING));
+    let mut packed = Vec::new();
+    for (id, handle) in members {
+        let Some(image) = images.get(handle) else {
+            warn!("Texture '{}' has no image data, skipping it.", id);
+            continue;
+        };
+        builder.add_texture(Some(handle.id()), image);
+        packed.push(id);
+    }
+    if packed.is_empty() { return 0; }
+    match builder.build() {
+        Ok((layout, image)) => {
+            let image = images.add(image);
+            let layout = layouts.add(layout);
+            for (index, id) in packed.into_iter().enumerate() {
+                leaves.insert(id.clone(), TextureLeaf {
+                    image: image.clone(),
+                    atlas: TextureAtlas { layout: layout.clone(), index },
+                });
+            }
+            1
+        }
+        Err(err) if members.len() > 1 => {
+            debug!("Splitting atlas of '{}': {:?}", family, err);
+            let (first, second) = members.split_at(members.len() / 2);
+            bake_family(family, first, images, layouts, leaves)
+                + bake_family(family, second, images, layouts, leaves)
+        }
+        Err(err) => {
+            error!("Could not bake texture '{}' into an atlas: {:?}", members[0].0, err);
+            0
+        }
+    }
+}
@@ -1,10 +1,73 @@
 #[derive(Debug)]
 pub enum LoadHandlerError {
-    RegexError(regex::Error)
+    RegexError(regex::Error),
+    IoError(std::io::Error),
+    ConfigError(ron::error::SpannedError),
+    UnknownCapture(String),
 }
 
 impl From<regex::Error> for LoadHandlerError {
     fn from(value: regex::Error) -> Self {
         LoadHandlerError::RegexError(value)
     }
-}
\ No newline at end of file
+}
+
+impl From<std::io::Error> for LoadHandlerError {
+    fn from(value: std::io::Error) -> Self {
+        LoadHandlerError::IoError(value)
+    }
+}
+
+impl From<ron::error::SpannedError> for LoadHandlerError {
+    fn from(value: ron::error::SpannedError) -> Self {
+        LoadHandlerError::ConfigError(value)
+    }
+}
+
+/// A problem with the texture folders. None of them stop the app, they are
+/// collected into the `AssetReport` instead.
+#[derive(Debug, Clone, PartialEq)]
+pub enum AssetError {
+    /// `load_handlers.ron` couldn't be read, nothing can be loaded.
+    InvalidHandlers(String),
+    /// A pack's or folder's `manifest.ron` couldn't be read, its textures go without.
+    InvalidManifest(String),
+    /// No load handler's `folder` pattern matches the folder.
+    UnmatchedFolder(String),
+    /// The file's name doesn't match its handler's `file` pattern.
+    UnmatchedFile { folder: String, file: String, pattern: String },
+    /// Another file already got the same id, the first one is kept.
+    DuplicateId { folder: String, file: String, id: super::id::Id },
+    /// The file has no image loader or isn't an image.
+    NotAnImage { folder: String, file: String },
+    /// The folder or one of its files couldn't be read.
+    LoadFailed { folder: String, reason: String },
+}
+
+impl AssetError {
+    /// The asset folder the problem is in, if it is in one.
+    pub fn folder(&self) -> Option<&str> {
+        match self {
+            AssetError::InvalidHandlers(_) | AssetError::InvalidManifest(_) => None,
+            AssetError::UnmatchedFolder(folder)
+            | AssetError::UnmatchedFile { folder, .. }
+            | AssetError::DuplicateId { folder, .. }
+            | AssetError::NotAnImage { folder, .. }
+            | AssetError::LoadFailed { folder, .. } => Some(folder),
+        }
+    }
+}
+
+impl std::fmt::Display for AssetError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            AssetError::InvalidHandlers(reason) => write!(f, "Could not read the load handlers: {}", reason),
+            AssetError::InvalidManifest(reason) => write!(f, "Could not read a pack manifest: {}", reason),
+            AssetError::UnmatchedFolder(folder) => write!(f, "{}: no load handler matches the folder", folder),
+            AssetError::UnmatchedFile { folder, file, pattern } => write!(f, "{}/{}: does not match '{}'", folder, file, pattern),
+            AssetError::DuplicateId { folder, file, id } => write!(f, "{}/{}: '{}' is already taken", folder, file, id),
+            AssetError::NotAnImage { folder, file } => write!(f, "{}/{}: not an image", folder, file),
+            AssetError::LoadFailed { folder, reason } => write!(f, "{}: {}", folder, reason),
+        }
+    }
+}
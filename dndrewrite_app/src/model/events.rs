@@ -0,0 +1,5 @@
+use bevy::prelude::Event;
+
+/// Sent once textures changed on disk were loaded into the texture tree again.
+#[derive(Event, Debug, Clone)]
+pub struct TexturesReloaded;
@@ -1,4 +1,5 @@
-#[derive(Eq, PartialEq, Hash, Ord, PartialOrd, Debug, Clone, Default, Deref, DerefMut)]
+#[derive(Eq, PartialEq, Hash, Ord, PartialOrd, Debug, Clone, Default, Deref, DerefMut, Serialize, Deserialize)]
+#[serde(transparent)]
 pub struct Id(pub(super) Vec<String>);
 
 
@@ -9,6 +10,7 @@ macro_rules! id {
 }
 use std::fmt::Display;
 use bevy::prelude::{Deref, DerefMut};
+use serde::{Deserialize, Serialize};
 pub(crate) use id;
 
 impl Id {
@@ -3,12 +3,24 @@ use bevy::asset::Handle;
 use bevy::prelude::*;
 use bevy::utils::HashMap;
 use regex::Regex;
+use serde::Deserialize;
 use super::*;
 
+/// A load handler as declared in the assets folder's `load_handlers.ron`.
+#[derive(Deserialize, Debug, Clone)]
+pub struct LoadHandlerConfig {
+    pub folder: String,
+    pub file: String,
+    pub id: Vec<String>,
+}
+
+/// A texture of a folder with its id, or why the file got none.
+pub type HandledTexture = Result<(id::Id, String, Handle<Image>), errors::AssetError>;
+
 pub struct LoadHandler
 {
     pattern: Regex,
-    handler: Box<dyn Fn(HashMap<String, String>, HashMap<String, Handle<Image>>) -> Vec<(id::Id, Handle<Image>)> + Send + Sync>,
+    handler: Box<dyn Fn(&str, HashMap<String, String>, HashMap<String, Handle<Image>>) -> Vec<HandledTexture> + Send + Sync>,
 }
 
 impl LoadHandler
@@ -17,7 +29,7 @@ impl LoadHandler
         pattern: impl Borrow<str>,
         handle_closure: F,
     ) -> Result<Self, errors::LoadHandlerError>
-    where F: Fn(HashMap<String, String>, HashMap<String, Handle<Image>>) -> Vec<(id::Id, Handle<Image>)> + Send + Sync + 'static
+    where F: Fn(&str, HashMap<String, String>, HashMap<String, Handle<Image>>) -> Vec<HandledTexture> + Send + Sync + 'static
     {
         let regex = Regex::new(pattern.borrow())?;
         let handler = Box::new(handle_closure);
@@ -26,11 +38,56 @@ impl LoadHandler
             handler,
         })
     }
+
+    pub fn from_config(config: LoadHandlerConfig) -> Result<Self, errors::LoadHandlerError> {
+        let folder_regex = Regex::new(&config.folder)?;
+        let file_regex = Regex::new(&config.file)?;
+        let placeholder_regex = Regex::new(r"\{(?<name>\w+)\}")?;
+        let capture_names: Vec<String> = folder_regex.capture_names()
+            .chain(file_regex.capture_names())
+            .flatten()
+            .map(str::to_owned)
+            .collect();
+        for segment in &config.id {
+            for placeholder in placeholder_regex.captures_iter(segment) {
+                let name = &placeholder["name"];
+                if !capture_names.iter().any(|capture| capture == name) {
+                    return Err(errors::LoadHandlerError::UnknownCapture(name.to_owned()));
+                }
+            }
+        }
+        let template = config.id;
+        Self::new(config.folder, move |folder, captures, handles| {
+            handles.into_iter()
+                .map(|(file, handle)| {
+                    let Some(file_captures) = file_regex.captures(&file) else {
+                        return Err(errors::AssetError::UnmatchedFile {
+                            folder: folder.to_owned(),
+                            file,
+                            pattern: file_regex.to_string(),
+                        });
+                    };
+                    let lookup = |name: &str| file_captures.name(name)
+                        .map(|value| value.as_str())
+                        .or_else(|| captures.get(name).map(String::as_str))
+                        .unwrap_or_default()
+                        .to_owned();
+                    let segments = template.iter()
+                        .map(|segment| placeholder_regex
+                            .replace_all(segment, |placeholder: &regex::Captures| lookup(&placeholder["name"]))
+                            .into_owned())
+                        .collect();
+                    Ok((id::Id(segments), file, handle))
+                })
+                .collect()
+        })
+    }
+
     pub fn pattern(&self) -> &Regex {
         &self.pattern
     }
 
-    pub fn handle(&self, captures: HashMap<String, String>, handles: HashMap<String, Handle<Image>>) -> Vec<(id::Id, Handle<Image>)> {
-        (self.handler)(captures, handles)
+    pub fn handle(&self, folder: &str, captures: HashMap<String, String>, handles: HashMap<String, Handle<Image>>) -> Vec<HandledTexture> {
+        (self.handler)(folder, captures, handles)
     }
-}
\ No newline at end of file
+}
@@ -1,123 +1,221 @@
-use bevy::asset::{AssetPath, AssetServer, Handle, LoadedFolder, RecursiveDependencyLoadState};
-use bevy::log::error;
+use bevy::asset::{AssetServer, Handle, LoadedFolder, LoadState, RecursiveDependencyLoadState};
+use std::path::Path;
+use bevy::ecs::system::SystemParam;
 use bevy::prelude::*;
-use bevy::utils::HashMap;
-use regex::Regex;
+use bevy::utils::{HashMap, HashSet};
 use crate::app::resources::AppLoaded;
 use crate::model::texture_tree::TextureNode;
-use crate::model::id::Id;
 use super::*;
 
+const LOAD_HANDLERS_FILE: &str = "load_handlers.ron";
+
 pub fn load(
     asset_server: Res<AssetServer>,
+    roots: Res<resources::AssetRoots>,
     mut folders_loading: ResMut<resources::FoldersLoading>,
+    mut report: ResMut<resources::AssetReport>,
+    mut commands: Commands,
 ) {
-    let Ok(folders) = std::fs::read_dir("../../assets") else { panic!("No 'assets' folder.") };
-    for folder in folders {
-        let Ok(folder) = folder else {
-            error!("IO error `{}` while loading asset folder.", folder.unwrap_err().kind());
-            continue;
-        };
-        let folder_name = folder.file_name().to_string_lossy().into_owned();
-        let path = folder.file_name();
-        let folder_handle = asset_server.load_folder(path.to_string_lossy().into_owned());
-        folders_loading.0.push((folder_name, folder_handle));
+    for (path, reason) in &roots.problems {
+        report.record(errors::AssetError::LoadFailed { folder: path.clone(), reason: reason.clone() });
     }
+    let folders = watcher::snapshot(&roots);
+    let mut names: Vec<&resources::AssetFolder> = folders.keys().collect();
+    names.sort();
+    for folder in names {
+        let folder_handle = asset_server.load_folder(roots.asset_path(folder));
+        folders_loading.0.push((folder.clone(), folder_handle));
+    }
+    info!("Loading {} folders from {} asset packs.", folders.len(), roots.roots().len());
+    commands.insert_resource(resources::AssetsWatcher::new(&roots, folders));
 }
 
+/// Reads the load handlers of every pack, the ones of later packs are tried
+/// first so they can take over folders.
 pub fn register_load_handlers(
+    roots: Res<resources::AssetRoots>,
+    mut report: ResMut<resources::AssetReport>,
     mut commands: Commands
 ) {
-    let tile_regex = Regex::new(r"(?<number>\d+)\.\w*$").unwrap();
-    let regex_clone = tile_regex.clone();
     let mut load_handlers = Vec::new();
-    load_handlers.push(load_handler::LoadHandler::new(
-        r"^pointy\.(?<tile_type>\w+)_(?<tile_variant>[A-Za-z0-9]*)\.(?<color>\w*)$",
-        move |captures, handles| {
-            let tile_type = captures.get("tile_type").unwrap();
-            let tile_variant = captures.get("tile_variant").unwrap();
-            let color = captures.get("color").unwrap();
-            handles.into_iter()
-                .map(|(file, handle)| {
-                    let captures = regex_clone.captures(&file).unwrap();
-                    let number_str = captures.name("number").unwrap().as_str();
-                    (id::id![tile_type, tile_variant, color, number_str], handle)
-                })
-                .collect()
-        },
-    ).unwrap());
-    load_handlers.push(load_handler::LoadHandler::new(
-        r"^overlay_(?<name>\w+)\.standard_full$",
-        move |captures, handles| {
-            let name = captures.get("name").unwrap();
-            handles.into_iter()
-                .map(|(file, handle)| {
-                    let captures = tile_regex.captures(&file).unwrap();
-                    let number_str = captures.name("number").unwrap().as_str();
-                    (id::id!["overlay", name, number_str], handle)
-                })
-                .collect()
-        },
-    ).unwrap());
+    for root in roots.roots().iter().rev() {
+        let path = root.join(LOAD_HANDLERS_FILE);
+        //Packs may rely on the handlers of the packs before them
+        if !path.exists() && roots.roots().len() > 1 { continue; }
+        match read_load_handlers(&path) {
+            Ok(handlers) => load_handlers.extend(handlers),
+            Err(err) => report.record(errors::AssetError::InvalidHandlers(format!("'{}': {:?}", path.display(), err))),
+        }
+    }
+    info!("Registered {} load handlers.", load_handlers.len());
     commands.insert_resource(resources::LoadHandlers(load_handlers));
 }
 
+fn read_load_handlers(path: &Path) -> Result<Vec<load_handler::LoadHandler>, errors::LoadHandlerError> {
+    let contents = std::fs::read_to_string(path)?;
+    let configs: Vec<load_handler::LoadHandlerConfig> = ron::from_str(&contents)?;
+    configs.into_iter()
+        .map(load_handler::LoadHandler::from_config)
+        .collect()
+}
+
+/// Where the textures of loaded folders end up, and the packs they're from.
+#[derive(SystemParam)]
+pub struct LoadedTextures<'w> {
+    roots: Res<'w, resources::AssetRoots>,
+    watcher: Res<'w, resources::AssetsWatcher>,
+    textures: ResMut<'w, resources::TexturesMap>,
+    folder_textures: ResMut<'w, resources::FolderTextures>,
+    report: ResMut<'w, resources::AssetReport>,
+}
+
+/// Whether every file of a folder finished loading, one way or the other.
+fn is_settled(asset_server: &AssetServer, loaded_folders: &Assets<LoadedFolder>, handle: &Handle<LoadedFolder>) -> bool {
+    let Some(folder) = loaded_folders.get(handle) else {
+        return matches!(asset_server.load_state(handle), LoadState::Failed(_));
+    };
+    folder.handles.iter()
+        .all(|handle| matches!(asset_server.load_state(handle.id()), LoadState::Loaded | LoadState::Failed(_)))
+}
+
 pub fn check_load_handlers(
     load_handlers: Res<resources::LoadHandlers>,
     asset_server: Res<AssetServer>,
-    mut textures: ResMut<resources::TexturesMap>,
+    mut loaded: LoadedTextures,
     mut folders_loading: ResMut<resources::FoldersLoading>,
     mut loaded_folders: ResMut<Assets<LoadedFolder>>,
     mut commands: Commands,
 ) {
-    for (name, handle) in std::mem::take(&mut folders_loading.0) {
+    for (folder, handle) in std::mem::take(&mut folders_loading.0) {
         let load_state = asset_server.recursive_dependency_load_state(handle.id());
-        if !matches!(load_state, RecursiveDependencyLoadState::Loaded) {
-            folders_loading.0.push((name, handle));
+        let done = match load_state {
+            RecursiveDependencyLoadState::Loaded => true,
+            //The files that did load are still used
+            RecursiveDependencyLoadState::Failed => is_settled(&asset_server, &loaded_folders, &handle),
+            _ => false,
+        };
+        if !done {
+            folders_loading.0.push((folder, handle));
             continue;
         }
-        let loaded_folder = loaded_folders.remove(handle.id()).unwrap();
-        for load_handler in &load_handlers.0 {
-            let Some(captures) = load_handler.pattern().captures(&name) else { continue; };
-            //Found our handler
-            let mut capture_map = HashMap::new();
-            for capture_name in load_handler.pattern().capture_names().skip(1) {
-                let Some(name) = capture_name else { continue; };
-                let Some(value) = captures.name(name) else { continue };
-                capture_map.insert(name.to_owned(), value.as_str().to_owned());
+        let name = loaded.roots.describe(&folder);
+        let Some(loaded_folder) = loaded_folders.remove(handle.id()) else {
+            loaded.report.record(errors::AssetError::LoadFailed {
+                folder: name,
+                reason: "the folder could not be read".to_owned(),
+            });
+            continue;
+        };
+        let Some(load_handler) = load_handlers.0.iter().find(|load_handler| load_handler.pattern().is_match(&folder.name)) else {
+            loaded.report.record(errors::AssetError::UnmatchedFolder(name));
+            continue;
+        };
+        let captures = load_handler.pattern().captures(&folder.name).unwrap();
+        let mut capture_map = HashMap::new();
+        for capture_name in load_handler.pattern().capture_names().skip(1) {
+            let Some(name) = capture_name else { continue; };
+            let Some(value) = captures.name(name) else { continue };
+            capture_map.insert(name.to_owned(), value.as_str().to_owned());
+        }
+        let mut handles: HashMap<String, Handle<Image>> = HashMap::new();
+        let mut seen = HashSet::new();
+        for handle in loaded_folder.handles {
+            let file = handle.path()
+                .and_then(|path| path.path().file_name())
+                .and_then(|file_name| file_name.to_str())
+                .map(str::to_owned);
+            let Some(file) = file else {
+                loaded.report.record(errors::AssetError::LoadFailed {
+                    folder: name.clone(),
+                    reason: "an asset has no file name".to_owned(),
+                });
+                continue;
+            };
+            seen.insert(file.clone());
+            if let LoadState::Failed(err) = asset_server.load_state(handle.id()) {
+                loaded.report.record(errors::AssetError::LoadFailed {
+                    folder: name.clone(),
+                    reason: format!("{}: {}", file, err),
+                });
+                continue;
+            }
+            match handle.try_typed::<Image>() {
+                Ok(handle) => { handles.insert(file, handle); }
+                Err(_) => loaded.report.record(errors::AssetError::NotAnImage { folder: name.clone(), file }),
+            }
+        }
+        //Files without an asset loader are silently left out of the folder
+        for path in loaded.watcher.folders.get(&folder).into_iter().flat_map(|files| files.keys()) {
+            let file = path.rsplit('/').next().unwrap_or(path);
+            if !seen.contains(file) && path != manifest::MANIFEST_FILE {
+                loaded.report.record(errors::AssetError::NotAnImage { folder: name.clone(), file: file.to_owned() });
             }
-            let handles: HashMap<String, Handle<Image>> = loaded_folder.handles
-                .into_iter()
-                .map(UntypedHandle::typed::<Image>)
-                .map(|handle| {
-                    let Some(path) = handle.path().map(AssetPath::path) else { return Err(handle.clone()) };
-                    let Some(file_name) = path.file_name() else { return Err(handle.clone()) };
-                    let Some(file_name_str) = file_name.to_str() else { return Err(handle.clone()) };
-                    Ok((file_name_str.to_owned(), handle))
-                })
-                .filter_map(Result::ok)
-                .collect();
-            //Handle loading
-            for (id, handle) in load_handler.handle(capture_map, handles) {
-                textures.0.insert(id, handle);
+        }
+        //Handle loading
+        for texture in load_handler.handle(&name, capture_map, handles) {
+            let (id, file, handle) = match texture {
+                Ok(texture) => texture,
+                Err(err) => {
+                    loaded.report.record(err);
+                    continue;
+                }
+            };
+            match loaded.folder_textures.claim(&folder, &id) {
+                Ok(true) => { loaded.textures.0.insert(id, handle); }
+                //A later pack has its own
+                Ok(false) => {}
+                Err(_) => loaded.report.record(errors::AssetError::DuplicateId { folder: name.clone(), file, id }),
             }
-            break;
         }
         info!("Loaded folder '{}'.", name);
     }
     if folders_loading.0.is_empty() {
         commands.remove_resource::<resources::FoldersLoading>();
-        info!("Assets loaded!");
+        info!("Assets loaded with {} problems!", loaded.report.errors().len());
     }
 }
 
+/// The texture tree being built or patched, and what goes into it besides
+/// the textures.
+#[derive(SystemParam)]
+pub struct TreeUpdate<'w> {
+    texture_tree: Option<ResMut<'w, resources::TextureTreeResource>>,
+    stale: Option<Res<'w, resources::StaleTextures>>,
+    roots: Res<'w, resources::AssetRoots>,
+    watcher: Res<'w, resources::AssetsWatcher>,
+    folder_textures: Res<'w, resources::FolderTextures>,
+    report: ResMut<'w, resources::AssetReport>,
+}
+
 pub fn transform_textures(
     mut commands: Commands,
     mut textures_map: ResMut<resources::TexturesMap>,
+    mut images: ResMut<Assets<Image>>,
+    mut layouts: ResMut<Assets<TextureAtlasLayout>>,
+    mut update: TreeUpdate,
+    mut reloaded: EventWriter<events::TexturesReloaded>,
 ) {
     let map = std::mem::take(&mut textures_map.0);
     commands.remove_resource::<resources::TexturesMap>();
-    let tree = TextureNode::new(map);
+    let leaves = atlas::bake(map, &mut images, &mut layouts);
+    let manifests = manifest::read_manifests(&update.roots, &update.watcher, &mut update.report);
+    update.report.write();
+    //Reloads patch the tree the rest of the app already holds on to
+    if let Some(texture_tree) = update.texture_tree.as_mut() {
+        commands.remove_resource::<resources::StaleTextures>();
+        for id in update.stale.iter().flat_map(|stale| stale.iter()) {
+            texture_tree.remove(id);
+        }
+        for (id, leaf) in leaves {
+            texture_tree[&id] = TextureNode::leaf_node(leaf);
+        }
+        manifest::apply(texture_tree, &manifests, &update.folder_textures);
+        info!("Textures reloaded!");
+        reloaded.send(events::TexturesReloaded);
+        return;
+    }
+    let mut tree = TextureNode::new(leaves);
+    manifest::apply(&mut tree, &manifests, &update.folder_textures);
     commands.insert_resource(resources::TextureTreeResource(tree));
     commands.insert_resource(AppLoaded);
 }
\ No newline at end of file
@@ -0,0 +1,165 @@
+use std::collections::BTreeMap;
+use std::path::Path;
+use bevy::log::warn;
+use serde::Deserialize;
+use super::*;
+use super::resources::AssetFolder;
+use super::texture_tree::TextureNode;
+
+/// Optional file at the top of an asset pack or of one of its texture folders.
+pub const MANIFEST_FILE: &str = "manifest.ron";
+
+/// Names and credits the textures of an asset pack. The pack's author and
+/// license apply to every texture it loads unless a node names its own.
+/// A folder's manifest does the same for the textures of that folder, over
+/// the pack's.
+#[derive(Deserialize, Debug, Clone, Default)]
+#[serde(default)]
+pub struct PackManifest {
+    pub name: Option<String>,
+    pub description: Option<String>,
+    pub author: Option<String>,
+    pub license: Option<String>,
+    /// By texture id, like `grassland/dense` for a branch or
+    /// `overlay/figure/standard_full/dracolich` for a leaf.
+    pub nodes: BTreeMap<String, TextureMeta>,
+}
+
+#[derive(Deserialize, Debug, Clone, Default, PartialEq)]
+#[serde(default)]
+pub struct TextureMeta {
+    pub name: Option<String>,
+    pub description: Option<String>,
+    pub author: Option<String>,
+    pub license: Option<String>,
+    pub tags: Vec<String>,
+    /// Lower comes first in the admin menu, nodes without one come last.
+    pub order: Option<i32>,
+    /// Name of the pack the texture came from.
+    #[serde(skip)]
+    pub pack: Option<String>,
+}
+
+impl TextureMeta {
+    fn from_pack(pack: &PackManifest) -> Self {
+        Self {
+            author: pack.author.clone(),
+            license: pack.license.clone(),
+            pack: pack.name.clone(),
+            ..Self::default()
+        }
+    }
+
+    /// `other`'s fields, falling back to ours where it has none.
+    fn merge(&self, other: &TextureMeta) -> Self {
+        Self {
+            name: other.name.clone().or_else(|| self.name.clone()),
+            description: other.description.clone().or_else(|| self.description.clone()),
+            author: other.author.clone().or_else(|| self.author.clone()),
+            license: other.license.clone().or_else(|| self.license.clone()),
+            tags: if other.tags.is_empty() { self.tags.clone() } else { other.tags.clone() },
+            order: other.order.or(self.order),
+            pack: other.pack.clone().or_else(|| self.pack.clone()),
+        }
+    }
+
+    /// Admin menu tooltip, `name` is the node's name in the texture tree.
+    pub fn tooltip(&self, name: &str) -> String {
+        let mut lines = vec![self.name.clone().unwrap_or_else(|| name.replace('_', " "))];
+        lines.extend(self.description.clone());
+        if !self.tags.is_empty() {
+            lines.push(format!("Tags: {}", self.tags.join(", ")));
+        }
+        let credit = match (&self.author, &self.license) {
+            (Some(author), Some(license)) => Some(format!("By {}, {}", author, license)),
+            (Some(author), None) => Some(format!("By {}", author)),
+            (None, Some(license)) => Some(license.clone()),
+            (None, None) => None,
+        };
+        lines.extend(credit);
+        lines.extend(self.pack.as_ref().map(|pack| format!("From {}", pack)));
+        lines.join("\n")
+    }
+}
+
+/// The manifest of every pack and of the texture folders that have one.
+#[derive(Debug, Default)]
+pub(super) struct Manifests {
+    /// By pack, `None` for packs without one.
+    packs: Vec<Option<PackManifest>>,
+    folders: BTreeMap<AssetFolder, PackManifest>,
+}
+
+impl Manifests {
+    /// What a texture of the folder is described as before its own node.
+    fn defaults(&self, folder: &AssetFolder) -> Option<TextureMeta> {
+        let pack = self.packs.get(folder.root).and_then(Option::as_ref).map(TextureMeta::from_pack);
+        let own = self.folders.get(folder).map(TextureMeta::from_pack);
+        match (pack, own) {
+            (Some(pack), Some(own)) => Some(pack.merge(&own)),
+            (pack, own) => pack.or(own),
+        }
+    }
+}
+
+fn read_manifest(path: &Path, report: &mut resources::AssetReport) -> Option<PackManifest> {
+    let contents = std::fs::read_to_string(path).ok()?;
+    match ron::from_str(&contents) {
+        Ok(manifest) => Some(manifest),
+        Err(err) => {
+            report.record(errors::AssetError::InvalidManifest(format!("'{}': {}", path.display(), err)));
+            None
+        }
+    }
+}
+
+/// Reads the manifests of every pack, and of the folders the watcher saw one in.
+pub(super) fn read_manifests(
+    roots: &resources::AssetRoots,
+    watcher: &resources::AssetsWatcher,
+    report: &mut resources::AssetReport,
+) -> Manifests {
+    let packs = roots.roots().iter()
+        .map(|root| read_manifest(&root.join(MANIFEST_FILE), report))
+        .collect();
+    let mut folders = BTreeMap::new();
+    for (folder, files) in &watcher.folders {
+        if !files.contains_key(MANIFEST_FILE) { continue; }
+        let Some(root) = roots.roots().get(folder.root) else { continue; };
+        if let Some(manifest) = read_manifest(&root.join(&folder.name).join(MANIFEST_FILE), report) {
+            folders.insert(folder.clone(), manifest);
+        }
+    }
+    Manifests { packs, folders }
+}
+
+/// Attaches the manifests' metadata to the tree, later packs overriding
+/// earlier ones and folders overriding their pack.
+pub(super) fn apply(
+    tree: &mut TextureNode,
+    manifests: &Manifests,
+    folder_textures: &resources::FolderTextures,
+) {
+    tree.clear_meta();
+    for (id, folder) in &folder_textures.owners {
+        let Some(meta) = manifests.defaults(folder) else { continue; };
+        let Some(node) = tree.get_mut(id) else { continue; };
+        node.1 = Some(meta);
+    }
+    for (root, pack) in manifests.packs.iter().enumerate() {
+        let folders = manifests.folders.iter()
+            .filter(|(folder, _)| folder.root == root)
+            .map(|(_, manifest)| manifest);
+        for manifest in pack.iter().chain(folders) {
+            for (path, meta) in &manifest.nodes {
+                let id = id::Id(path.split('/').map(str::to_owned).collect());
+                let Some(node) = tree.get_mut(&id) else {
+                    warn!("Manifest of '{}' describes '{}', which wasn't loaded.", manifest.name.as_deref().unwrap_or("a pack"), path);
+                    continue;
+                };
+                let base = node.1.clone().unwrap_or_else(|| TextureMeta::from_pack(manifest));
+                node.1 = Some(base.merge(meta));
+            }
+        }
+    }
+}
@@ -8,6 +8,9 @@ impl Plugin for LoaderPlugin {
         app
             .init_resource::<resources::FoldersLoading>()
             .init_resource::<resources::TexturesMap>()
+            .init_resource::<resources::FolderTextures>()
+            .init_resource::<resources::AssetReport>()
+            .add_event::<events::TexturesReloaded>()
             .add_systems(Startup, (
                 loading::load,
                 loading::register_load_handlers
@@ -17,6 +20,9 @@ impl Plugin for LoaderPlugin {
                     .run_if(resource_exists::<resources::FoldersLoading>),
                 loading::transform_textures
                     .run_if(resource_removed::<resources::FoldersLoading>()),
+                watcher::watch_assets
+                    .run_if(resource_exists::<resources::TextureTreeResource>)
+                    .run_if(not(resource_exists::<resources::FoldersLoading>)),
             ));
     }
 }
\ No newline at end of file
@@ -0,0 +1,31 @@
+use bevy::prelude::*;
+use super::*;
+
+/// Where the report of the last load is written, next to the settings file.
+const REPORT_FILE: &str = "asset_report.log";
+
+impl resources::AssetReport {
+    pub fn record(&mut self, error: errors::AssetError) {
+        warn!("{}", error);
+        self.0.push(error);
+    }
+
+    /// Drops the problems of a folder that's about to be loaded again.
+    pub fn forget_folder(&mut self, folder: &str) {
+        self.0.retain(|error| error.folder() != Some(folder));
+    }
+
+    pub fn errors(&self) -> &[errors::AssetError] {
+        &self.0
+    }
+
+    pub fn write(&self) {
+        let mut contents = format!("{} asset problems\n", self.0.len());
+        for error in &self.0 {
+            contents.push_str(&format!("{}\n", error));
+        }
+        if let Err(err) = std::fs::write(REPORT_FILE, contents) {
+            error!("Could not write the asset report to '{}': {}", REPORT_FILE, err);
+        }
+    }
+}
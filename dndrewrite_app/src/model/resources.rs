@@ -1,11 +1,30 @@
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+use std::time::SystemTime;
 use bevy::asset::{Handle, LoadedFolder};
-use bevy::prelude::{Deref, DerefMut, Image, Resource};
+use bevy::prelude::{Deref, DerefMut, Image, Resource, Timer};
 use bevy::utils::HashMap;
 
 use super::*;
 
+/// The asset packs textures are loaded from, later ones overriding the
+/// textures of earlier ones.
+#[derive(Resource, Debug, Clone, Default)]
+pub struct AssetRoots {
+    pub(super) roots: Vec<PathBuf>,
+    /// Roots and settings that couldn't be used, with the reason why.
+    pub(super) problems: Vec<(String, String)>,
+}
+
+/// A texture folder, `root` being its pack's index in the `AssetRoots`.
+#[derive(Debug, Clone, Eq, PartialEq, Hash, Ord, PartialOrd)]
+pub struct AssetFolder {
+    pub(super) root: usize,
+    pub(super) name: String,
+}
+
 #[derive(Resource, Default, Deref, DerefMut)]
-pub struct FoldersLoading(pub(super) Vec<(String, Handle<LoadedFolder>)>);
+pub struct FoldersLoading(pub(super) Vec<(AssetFolder, Handle<LoadedFolder>)>);
 
 #[derive(Resource, Default, Deref, DerefMut)]
 pub struct LoadHandlers(pub(super) Vec<load_handler::LoadHandler>);
@@ -14,4 +33,32 @@ pub struct LoadHandlers(pub(super) Vec<load_handler::LoadHandler>);
 pub struct TexturesMap(pub(super) HashMap<id::Id, Handle<Image>>);
 
 #[derive(Resource, Debug, Deref, DerefMut)]
-pub struct TextureTreeResource(pub(crate) texture_tree::TextureNode);
\ No newline at end of file
+pub struct TextureTreeResource(pub(crate) texture_tree::TextureNode);
+
+/// Ids every asset folder provides, overridden ones included, so they can be
+/// dropped when the folder changes, and the folder each id currently comes from.
+#[derive(Resource, Default)]
+pub struct FolderTextures {
+    pub(super) ids: HashMap<AssetFolder, Vec<id::Id>>,
+    pub(super) owners: HashMap<id::Id, AssetFolder>,
+}
+
+/// Ids of the folders being reloaded, removed from the tree once the new
+/// textures are in.
+#[derive(Resource, Default, Deref, DerefMut)]
+pub struct StaleTextures(pub(super) Vec<id::Id>);
+
+/// Modification times of every file in an asset folder, by path within it.
+pub type FolderSnapshot = BTreeMap<String, SystemTime>;
+
+#[derive(Resource, Debug)]
+pub struct AssetsWatcher {
+    pub(super) timer: Timer,
+    pub(super) folders: HashMap<AssetFolder, FolderSnapshot>,
+    /// Modification times of the packs' manifests, by pack.
+    pub(super) manifests: Vec<Option<SystemTime>>,
+}
+
+/// Every problem found while loading the texture folders.
+#[derive(Resource, Debug, Default)]
+pub struct AssetReport(pub(super) Vec<errors::AssetError>);
@@ -0,0 +1,202 @@
+use std::collections::BTreeSet;
+use std::path::PathBuf;
+use bevy::asset::io::{AssetSource, AssetSourceId};
+use bevy::prelude::*;
+use super::*;
+use super::resources::AssetFolder;
+use super::settings::{SETTINGS_FILE, Settings};
+
+/// Asset packs to load, separated like `PATH`.
+pub const ASSETS_ENV_VAR: &str = "DNDREWRITE_ASSETS";
+/// Asset pack to load, can be given more than once.
+pub const ASSETS_ARG: &str = "--assets";
+/// Tried in order when no pack is configured.
+const DEFAULT_ROOTS: [&str; 3] = ["assets", "../assets", "../../assets"];
+
+impl resources::AssetRoots {
+    /// Collects the packs of the settings file, then the environment variable,
+    /// then the `--assets` arguments, each later one overriding the earlier.
+    pub fn resolve(args: &[String]) -> Self {
+        let mut roots = Self::default();
+        let mut paths = match Settings::read() {
+            Ok(settings) => settings.asset_roots,
+            Err(err) => {
+                roots.problems.push((SETTINGS_FILE.to_owned(), err));
+                Vec::new()
+            }
+        };
+        if let Some(value) = std::env::var_os(ASSETS_ENV_VAR) {
+            paths.extend(std::env::split_paths(&value));
+        }
+        paths.extend(args.windows(2)
+            .filter(|pair| pair[0] == ASSETS_ARG)
+            .map(|pair| PathBuf::from(&pair[1])));
+        if paths.is_empty() {
+            paths.extend(DEFAULT_ROOTS.iter().map(PathBuf::from).find(|path| path.is_dir()));
+        }
+        for path in paths {
+            //Bevy resolves relative paths against the executable, not the working directory
+            match std::fs::canonicalize(&path) {
+                Ok(root) if root.is_dir() => roots.roots.push(root),
+                Ok(_) => roots.problems.push((path.display().to_string(), "not a folder".to_owned())),
+                Err(err) => roots.problems.push((path.display().to_string(), err.to_string())),
+            }
+        }
+        if roots.roots.is_empty() && roots.problems.is_empty() {
+            roots.problems.push(("assets".to_owned(), "no asset folder found".to_owned()));
+        }
+        roots
+    }
+
+    /// Registers every pack as an asset source, has to happen before the
+    /// `AssetPlugin` is added.
+    pub fn register(&self, app: &mut App) {
+        for (index, root) in self.roots.iter().enumerate() {
+            let path = root.to_string_lossy().into_owned();
+            app.register_asset_source(
+                AssetSourceId::Name(Self::source(index).into()),
+                AssetSource::build().with_reader(AssetSource::get_default_reader(path)),
+            );
+        }
+    }
+
+    fn source(index: usize) -> String {
+        format!("pack{}", index)
+    }
+
+    pub fn roots(&self) -> &[PathBuf] {
+        &self.roots
+    }
+
+    /// Where the folder is on disk, used to name it in the report.
+    pub fn describe(&self, folder: &AssetFolder) -> String {
+        match self.roots.get(folder.root) {
+            Some(root) => root.join(&folder.name).display().to_string(),
+            None => folder.name.clone(),
+        }
+    }
+
+    /// The folder's path for the asset server.
+    pub fn asset_path(&self, folder: &AssetFolder) -> String {
+        format!("{}://{}", Self::source(folder.root), folder.name)
+    }
+}
+
+impl resources::FolderTextures {
+    /// Takes `id` for the folder's texture. Packs override the textures of
+    /// earlier packs and lose against later ones, two files of a folder or
+    /// two folders of the same pack can't share an id, the folder that got
+    /// it first is returned then.
+    pub fn claim(&mut self, folder: &AssetFolder, id: &id::Id) -> Result<bool, AssetFolder> {
+        if self.ids.get(folder).is_some_and(|ids| ids.contains(id)) {
+            return Err(folder.clone());
+        }
+        //Only ids that are already owned can have another provider
+        let same_pack = self.owners.contains_key(id).then(|| self.ids.iter()
+            .find(|(other, ids)| other.root == folder.root && *other != folder && ids.contains(id)))
+            .flatten();
+        if let Some((other, _)) = same_pack {
+            return Err(other.clone());
+        }
+        self.ids.entry(folder.clone()).or_default().push(id.clone());
+        let owner = self.owners.get(id);
+        if owner.is_some_and(|owner| owner.root > folder.root) {
+            return Ok(false);
+        }
+        if let Some(owner) = owner {
+            debug!("'{}' of pack {} is overridden by pack {}.", id, owner.root, folder.root);
+        }
+        self.owners.insert(id.clone(), folder.clone());
+        Ok(true)
+    }
+
+    /// Gives up every id of the folder, returning the ids it was the owner of
+    /// and the folders of earlier packs that have some of them too. Those
+    /// have to be loaded again to take their ids back.
+    pub fn release(&mut self, folder: &AssetFolder) -> (Vec<id::Id>, Vec<AssetFolder>) {
+        let ids = self.ids.remove(folder).unwrap_or_default();
+        let mut owned = Vec::new();
+        let mut shadowed = BTreeSet::new();
+        for id in ids {
+            if self.owners.get(&id) != Some(folder) { continue; }
+            self.owners.remove(&id);
+            let provider = self.ids.iter()
+                .filter(|(_, ids)| ids.contains(&id))
+                .map(|(provider, _)| provider)
+                .max_by_key(|provider| provider.root);
+            if let Some(provider) = provider {
+                shadowed.insert(provider.clone());
+            }
+            owned.push(id);
+        }
+        (owned, shadowed.into_iter().collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::model::id::{Id, id};
+    use super::*;
+
+    fn folder(root: usize, name: &str) -> AssetFolder {
+        AssetFolder { root, name: name.to_string() }
+    }
+
+    #[test]
+    fn later_packs_override_earlier_ones() {
+        let mut textures = resources::FolderTextures::default();
+        let id = id!("grass", "plain", "green", "1");
+        assert_eq!(textures.claim(&folder(0, "tiles"), &id), Ok(true));
+        assert_eq!(textures.claim(&folder(1, "tiles"), &id), Ok(true));
+        assert_eq!(textures.claim(&folder(0, "more_tiles"), &id), Err(folder(0, "tiles")));
+        assert_eq!(textures.owners[&id], folder(1, "tiles"));
+    }
+
+    #[test]
+    fn two_files_of_a_folder_cant_share_an_id() {
+        let mut textures = resources::FolderTextures::default();
+        let id = id!("grass", "plain", "green", "1");
+        assert_eq!(textures.claim(&folder(0, "tiles"), &id), Ok(true));
+        assert_eq!(textures.claim(&folder(0, "tiles"), &id), Err(folder(0, "tiles")));
+        assert_eq!(textures.ids[&folder(0, "tiles")], [id]);
+    }
+
+    #[test]
+    fn earlier_packs_lose_against_later_ones() {
+        let mut textures = resources::FolderTextures::default();
+        let id = id!("grass", "plain", "green", "1");
+        assert_eq!(textures.claim(&folder(1, "tiles"), &id), Ok(true));
+        assert_eq!(textures.claim(&folder(0, "tiles"), &id), Ok(false));
+        assert_eq!(textures.owners[&id], folder(1, "tiles"));
+    }
+
+    #[test]
+    fn releasing_an_override_gives_the_id_back() {
+        let mut textures = resources::FolderTextures::default();
+        let shared = id!("grass", "plain", "green", "1");
+        let own = id!("grass", "plain", "green", "2");
+        textures.claim(&folder(0, "tiles"), &shared).unwrap();
+        textures.claim(&folder(1, "tiles"), &shared).unwrap();
+        textures.claim(&folder(1, "tiles"), &own).unwrap();
+        let (stale, reload) = textures.release(&folder(1, "tiles"));
+        assert_eq!(stale, [shared.clone(), own]);
+        assert_eq!(reload, [folder(0, "tiles")]);
+        //The earlier pack is loaded again and takes its id back
+        let (stale, reload) = textures.release(&folder(0, "tiles"));
+        assert!(stale.is_empty());
+        assert!(reload.is_empty());
+        assert_eq!(textures.claim(&folder(0, "tiles"), &shared), Ok(true));
+    }
+
+    #[test]
+    fn releasing_a_shadowed_folder_keeps_the_override() {
+        let mut textures = resources::FolderTextures::default();
+        let id = id!("grass", "plain", "green", "1");
+        textures.claim(&folder(0, "tiles"), &id).unwrap();
+        textures.claim(&folder(1, "tiles"), &id).unwrap();
+        let (stale, reload) = textures.release(&folder(0, "tiles"));
+        assert!(stale.is_empty());
+        assert!(reload.is_empty());
+        assert_eq!(textures.owners[&id], folder(1, "tiles"));
+    }
+}
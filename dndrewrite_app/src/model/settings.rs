@@ -0,0 +1,36 @@
+use std::path::PathBuf;
+use ron::ser::PrettyConfig;
+use serde::{Deserialize, Serialize};
+use super::id::Id;
+
+/// Read from the working directory, every field is optional.
+pub const SETTINGS_FILE: &str = "settings.ron";
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+#[serde(default)]
+pub struct Settings {
+    /// Asset packs to load, later ones override textures of earlier ones.
+    pub asset_roots: Vec<PathBuf>,
+    /// The map saved with Ctrl+S and loaded with Ctrl+O.
+    pub map_file: Option<PathBuf>,
+    /// Textures starred in the admin palette.
+    pub favorites: Vec<Id>,
+    /// Most recently selected textures first.
+    pub recent: Vec<Id>,
+}
+
+impl Settings {
+    /// The settings file, or the defaults if there is none.
+    pub fn read() -> Result<Self, String> {
+        match std::fs::read_to_string(SETTINGS_FILE) {
+            Ok(contents) => ron::from_str(&contents).map_err(|err| err.to_string()),
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => Ok(Self::default()),
+            Err(err) => Err(err.to_string()),
+        }
+    }
+
+    pub fn write(&self) -> Result<(), String> {
+        let contents = ron::ser::to_string_pretty(self, PrettyConfig::default()).map_err(|err| err.to_string())?;
+        std::fs::write(SETTINGS_FILE, contents).map_err(|err| err.to_string())
+    }
+}
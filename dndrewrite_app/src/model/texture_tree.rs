@@ -4,18 +4,64 @@ use bevy::prelude::*;
 use bevy::utils::hashbrown::HashMap;
 use super::*;
 
+/// A baked texture, the atlas page it was packed into and its place there.
+#[derive(Debug, Clone, Default)]
+pub struct TextureLeaf {
+    pub image: Handle<Image>,
+    pub atlas: TextureAtlas,
+}
+
+impl TextureLeaf {
+    pub fn sprite(&self, transform: Transform) -> (SpriteBundle, TextureAtlas) {
+        (SpriteBundle {
+            texture: self.image.clone(),
+            transform,
+            ..default()
+        }, self.atlas.clone())
+    }
+}
+
+/// A branch or a leaf of the texture tree, with what the pack manifests say
+/// about it.
 #[derive(Debug, Deref, DerefMut, Clone)]
-pub struct TextureNode(pub Result<BTreeMap<String, TextureNode>, Handle<Image>>);
+pub struct TextureNode(#[deref] pub Result<BTreeMap<String, TextureNode>, TextureLeaf>, pub Option<manifest::TextureMeta>);
 
 impl TextureNode {
-    pub fn new(map: HashMap<id::Id, Handle<Image>>) -> Self {
-        let mut root = Self(Ok(BTreeMap::new()));
-        for (id, handle) in map {
-            root[&id] = Self(Err(handle));
+    pub fn new(map: HashMap<id::Id, TextureLeaf>) -> Self {
+        let mut root = Self::branch_node();
+        for (id, leaf) in map {
+            root[&id] = Self::leaf_node(leaf);
         }
         root
     }
 
+    pub fn branch_node() -> Self {
+        Self(Ok(BTreeMap::new()), None)
+    }
+
+    pub fn leaf_node(leaf: TextureLeaf) -> Self {
+        Self(Err(leaf), None)
+    }
+
+    pub fn meta(&self) -> Option<&manifest::TextureMeta> {
+        self.1.as_ref()
+    }
+
+    pub(super) fn clear_meta(&mut self) {
+        self.1 = None;
+        if let Ok(map) = &mut self.0 {
+            map.values_mut().for_each(TextureNode::clear_meta);
+        }
+    }
+
+    pub fn get_mut(&mut self, id: &id::Id) -> Option<&mut TextureNode> {
+        let mut current = self;
+        for name in &id.0 {
+            current = current.get_branch_mut(name)?;
+        }
+        Some(current)
+    }
+
     pub fn insert_branch(&mut self, name: String, branch: TextureNode) -> &mut TextureNode {
         let Ok(map) = &mut self.0 else { panic!("the node is a leaf") };
         map.entry(name).or_insert(branch)
@@ -39,7 +85,56 @@ impl TextureNode {
         map.get_mut(name)
     }
 
-    pub fn leaf(&self) -> Option<Handle<Image>> {
+    /// Removes the node at `id`, along with the branches it leaves empty.
+    pub fn remove(&mut self, id: &id::Id) -> Option<TextureNode> {
+        let (name, rest) = id.split_first()?;
+        let Ok(map) = &mut self.0 else { return None; };
+        if rest.is_empty() {
+            return map.remove(name);
+        }
+        let branch = map.get_mut(name)?;
+        let removed = branch.remove(&id::Id(rest.to_vec()));
+        if branch.branch().is_some_and(BTreeMap::is_empty) {
+            map.remove(name);
+        }
+        removed
+    }
+
+    pub fn get(&self, id: &id::Id) -> Option<&TextureNode> {
+        let mut current = self;
+        for name in &id.0 {
+            current = current.get_branch(name)?;
+        }
+        Some(current)
+    }
+
+    /// Path of the first leaf below this node, relative to it.
+    pub fn first_leaf_id(&self) -> Option<id::Id> {
+        match &self.0 {
+            Err(_) => Some(id::Id::default()),
+            Ok(map) => map.iter()
+                .find_map(|(name, node)| {
+                    let mut rest = node.first_leaf_id()?;
+                    rest.insert(0, name.clone());
+                    Some(rest)
+                }),
+        }
+    }
+
+    /// Paths of every leaf below this node, relative to it, in tree order.
+    pub fn leaf_ids(&self) -> Vec<id::Id> {
+        match &self.0 {
+            Err(_) => vec![id::Id::default()],
+            Ok(map) => map.iter()
+                .flat_map(|(name, node)| node.leaf_ids().into_iter().map(move |mut rest| {
+                    rest.insert(0, name.clone());
+                    rest
+                }))
+                .collect(),
+        }
+    }
+
+    pub fn leaf(&self) -> Option<TextureLeaf> {
         self.0.as_ref().err().cloned()
     }
 
@@ -47,7 +142,7 @@ impl TextureNode {
         self.0.as_ref().ok()
     }
 
-    pub fn result(&self) -> Result<&BTreeMap<String, TextureNode>, &Handle<Image>> {
+    pub fn result(&self) -> Result<&BTreeMap<String, TextureNode>, &TextureLeaf> {
         self.0.as_ref()
     }
 }
@@ -75,7 +170,7 @@ impl IndexMut<&id::Id> for TextureNode {
                         None => unreachable!()
                     };
                 }
-                current.insert_branch(name.clone(), Self(Ok(BTreeMap::new())))
+                current.insert_branch(name.clone(), Self::branch_node())
             };
             current = chosen;
         }
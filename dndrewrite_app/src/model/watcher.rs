@@ -0,0 +1,127 @@
+use std::collections::BTreeSet;
+use std::path::Path;
+use std::time::{Duration, SystemTime};
+use bevy::prelude::*;
+use bevy::utils::HashMap;
+use super::*;
+use super::resources::{AssetFolder, FolderSnapshot};
+
+/// How often the assets folder is scanned for changes.
+const WATCH_INTERVAL: Duration = Duration::from_secs(1);
+
+fn snapshot_folder(path: &Path, prefix: &str, snapshot: &mut FolderSnapshot) {
+    let Ok(entries) = std::fs::read_dir(path) else { return; };
+    for entry in entries.flatten() {
+        let name = format!("{}{}", prefix, entry.file_name().to_string_lossy());
+        let Ok(metadata) = entry.metadata() else { continue; };
+        if metadata.is_dir() {
+            snapshot_folder(&entry.path(), &format!("{}/", name), snapshot);
+        } else if let Ok(modified) = metadata.modified() {
+            snapshot.insert(name, modified);
+        }
+    }
+}
+
+/// Every texture folder of every asset pack with the files in it.
+pub(super) fn snapshot(roots: &resources::AssetRoots) -> HashMap<AssetFolder, FolderSnapshot> {
+    let mut folders = HashMap::new();
+    for (root, path) in roots.roots().iter().enumerate() {
+        let Ok(entries) = std::fs::read_dir(path) else { continue; };
+        for folder in entries.flatten() {
+            //Loose files like the load handler config aren't texture folders
+            if !folder.file_type().is_ok_and(|file_type| file_type.is_dir()) { continue; }
+            let mut snapshot = FolderSnapshot::new();
+            snapshot_folder(&folder.path(), "", &mut snapshot);
+            let name = folder.file_name().to_string_lossy().into_owned();
+            folders.insert(AssetFolder { root, name }, snapshot);
+        }
+    }
+    folders
+}
+
+/// When each pack's manifest was last changed, the folders' ones are in
+/// their snapshots.
+fn manifest_times(roots: &resources::AssetRoots) -> Vec<Option<SystemTime>> {
+    roots.roots().iter()
+        .map(|root| std::fs::metadata(root.join(manifest::MANIFEST_FILE)).and_then(|metadata| metadata.modified()).ok())
+        .collect()
+}
+
+impl resources::AssetsWatcher {
+    pub fn new(roots: &resources::AssetRoots, folders: HashMap<AssetFolder, FolderSnapshot>) -> Self {
+        Self {
+            timer: Timer::new(WATCH_INTERVAL, TimerMode::Repeating),
+            folders,
+            manifests: manifest_times(roots),
+        }
+    }
+
+    /// Rescans the assets folder, returning the folders that were added,
+    /// removed or had a file added, changed or removed since the last scan.
+    fn changed_folders(&mut self, roots: &resources::AssetRoots) -> Vec<AssetFolder> {
+        let folders = snapshot(roots);
+        let names: BTreeSet<&AssetFolder> = folders.keys().chain(self.folders.keys()).collect();
+        let changed = names.into_iter()
+            .filter(|name| folders.get(*name) != self.folders.get(*name))
+            .cloned()
+            .collect();
+        self.folders = folders;
+        changed
+    }
+
+    /// Whether a pack's manifest was added, changed or removed since the last scan.
+    fn manifests_changed(&mut self, roots: &resources::AssetRoots) -> bool {
+        let manifests = manifest_times(roots);
+        let changed = manifests != self.manifests;
+        self.manifests = manifests;
+        changed
+    }
+}
+
+/// Loads changed texture folders again, the textures they held are replaced
+/// once the new ones are baked. Manifests are read again on every reload.
+pub fn watch_assets(
+    time: Res<Time>,
+    asset_server: Res<AssetServer>,
+    roots: Res<resources::AssetRoots>,
+    mut watcher: ResMut<resources::AssetsWatcher>,
+    mut folder_textures: ResMut<resources::FolderTextures>,
+    mut report: ResMut<resources::AssetReport>,
+    mut commands: Commands,
+) {
+    if !watcher.timer.tick(time.delta()).just_finished() { return; }
+    let mut changed = watcher.changed_folders(&roots);
+    let manifests_changed = watcher.manifests_changed(&roots);
+    if changed.is_empty() && !manifests_changed { return; }
+    if manifests_changed {
+        info!("A pack manifest changed, applying the manifests again.");
+    }
+    let mut stale = Vec::new();
+    let mut folders_loading = Vec::new();
+    let mut index = 0;
+    while let Some(folder) = changed.get(index).cloned() {
+        index += 1;
+        let path = roots.describe(&folder);
+        let (ids, shadowed) = folder_textures.release(&folder);
+        stale.extend(ids);
+        //Textures of earlier packs the folder was overriding come back
+        for shadowed in shadowed {
+            if !changed.contains(&shadowed) {
+                changed.push(shadowed);
+            }
+        }
+        report.forget_folder(&path);
+        if !watcher.folders.contains_key(&folder) {
+            info!("Asset folder '{}' was removed.", path);
+            continue;
+        }
+        info!("Asset folder '{}' changed, reloading it.", path);
+        let handle = asset_server.load_folder(roots.asset_path(&folder));
+        folders_loading.push((folder, handle));
+    }
+    //Even with nothing to load, the removed textures are dropped once
+    //loading "finishes"
+    commands.insert_resource(resources::FoldersLoading(folders_loading));
+    commands.insert_resource(resources::TexturesMap::default());
+    commands.insert_resource(resources::StaleTextures(stale));
+}
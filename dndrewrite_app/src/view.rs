@@ -2,4 +2,6 @@ pub mod ui;
 pub mod resources;
 pub mod query;
 pub mod plugins;
-pub mod scrolling_list;
\ No newline at end of file
+pub mod scrolling_list;
+pub mod layers;
+pub mod grid;
\ No newline at end of file
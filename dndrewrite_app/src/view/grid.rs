@@ -0,0 +1,40 @@
+use bevy::prelude::*;
+use crate::map::brush::{from_offset, rectangle, to_offset};
+use crate::view::query::UIQuery;
+use super::*;
+
+const GRID_COLOR: Color = Color::srgba(0., 0., 0., 0.35);
+/// Zoomed far out the outlines would only blacken the map.
+const MAX_GRID_HEXES: usize = 12_000;
+
+pub fn toggle_grid(
+    keys: Res<ButtonInput<KeyCode>>,
+    mut grid: ResMut<resources::GridVisible>,
+) {
+    //Ctrl+G generates a new map
+    if keys.any_pressed([KeyCode::ControlLeft, KeyCode::ControlRight]) { return; }
+    if keys.just_pressed(KeyCode::KeyG) {
+        **grid = !**grid;
+    }
+}
+
+/// Outlines every hex the admin camera sees.
+pub fn draw_grid(
+    ui: UIQuery,
+    grid: Res<resources::GridVisible>,
+    mut gizmos: Gizmos,
+) {
+    if !**grid { return; }
+    let Some(visible) = ui.visible_world_rect(true) else { return; };
+    let (a, b) = (visible.min, visible.max);
+    let (a, b) = (to_offset(ui.layout.world_pos_to_hex(a)), to_offset(ui.layout.world_pos_to_hex(b)));
+    //One hex of margin, partly visible hexes at the edges are outlined too
+    let (left, right) = (a.0.min(b.0) - 1, a.0.max(b.0) + 1);
+    let (top, bottom) = (a.1.min(b.1) - 1, a.1.max(b.1) + 1);
+    if (right - left + 1) as usize * (bottom - top + 1) as usize > MAX_GRID_HEXES { return; }
+    let (a, b) = (from_offset(left, top), from_offset(right, bottom));
+    for hex in rectangle(a, b) {
+        let corners = ui.layout.hex_corners(hex);
+        gizmos.linestrip_2d(corners.into_iter().chain([corners[0]]), GRID_COLOR);
+    }
+}
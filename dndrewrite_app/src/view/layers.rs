@@ -0,0 +1,16 @@
+use bevy::render::view::RenderLayers;
+
+/// Layer seen by both windows, the default for every sprite.
+pub const SHARED_LAYER: usize = 0;
+/// GM-only things like markers, only drawn in the admin window.
+pub const ADMIN_LAYER: usize = 1;
+/// Player-only things like the fog of war, only drawn in the user window.
+pub const USER_LAYER: usize = 2;
+
+pub fn admin_camera_layers() -> RenderLayers {
+    RenderLayers::from_layers(&[SHARED_LAYER, ADMIN_LAYER])
+}
+
+pub fn user_camera_layers() -> RenderLayers {
+    RenderLayers::from_layers(&[SHARED_LAYER, USER_LAYER])
+}
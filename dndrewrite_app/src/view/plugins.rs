@@ -1,6 +1,6 @@
 use bevy::prelude::*;
 use hexx::{HexLayout, HexOrientation};
-use crate::app::admin;
+use crate::app::{admin, asset_report, initiative_panel, minimap, notes_panel, painting, palette, status_bar, texture_search, token_movement, tooltip};
 use crate::app::resources::AppLoaded;
 use super::*;
 
@@ -11,23 +11,86 @@ impl Plugin for UIPlugin {
         app
             .init_resource::<resources::Scale>()
             .init_resource::<resources::MouseLastPosition>()
+            .init_resource::<resources::GridVisible>()
             .insert_resource(resources::HexLayoutResource(HexLayout {
                 hex_size: Vec2::splat(105. * 3f32.sqrt()),
                 orientation: HexOrientation::Pointy,
                 ..default()
             }))
+            .add_systems(Startup, ui::setup_gizmos)
             .add_systems(First, ui::setup_ui
                 .run_if(resource_added::<AppLoaded>))
             .add_systems(FixedUpdate, (
-                ui::move_camera,
+                ui::move_camera.run_if(notes_panel::not_typing),
                 ui::zoom,
-                (ui::detect_press, ui::map_drag).chain(),
+                (ui::detect_press, ui::map_drag).chain()
+                    .run_if(admin::no_tool_selected),
             )
                 .run_if(resource_exists::<AppLoaded>))
             .add_systems(Update, (
                 ui::exit_on_esc,
                 scrolling_list::mouse_scroll,
+                admin::rebuild_admin_menus,
                 admin::handle_admin,
+                (
+                    texture_search::focus_texture_search,
+                    texture_search::type_texture_search,
+                    texture_search::render_texture_search,
+                ).chain().after(admin::handle_admin),
+                tooltip::show_tooltip,
+                admin::use_tool,
+                admin::draw_stroke,
+                (
+                    painting::brush_buttons,
+                    painting::brush_shortcuts.run_if(notes_panel::not_typing),
+                    painting::render_brush_panel,
+                    painting::toggle_season.run_if(notes_panel::not_typing),
+                    painting::render_season,
+                    painting::paint_tiles,
+                    painting::preview_brush,
+                ).chain(),
+                admin::tool_shortcuts.run_if(notes_panel::not_typing),
+                (
+                    palette::palette_buttons,
+                    palette::palette_shortcuts.run_if(notes_panel::not_typing),
+                    palette::remember_selection,
+                    palette::save_palette,
+                    palette::render_palette,
+                ).chain().after(admin::handle_admin),
+                (
+                    initiative_panel::forget_removed_combatants,
+                    initiative_panel::select_initiative_row,
+                    initiative_panel::initiative_shortcuts.run_if(notes_panel::not_typing),
+                    initiative_panel::render_initiative,
+                    initiative_panel::highlight_active_combatant,
+                    token_movement::reset_movement_on_turn,
+                ).chain(),
+                (
+                    token_movement::drag_token.run_if(token_movement::move_tool_selected),
+                    token_movement::draw_token_path,
+                ).chain(),
+                (
+                    notes_panel::sync_note_draft,
+                    notes_panel::note_buttons,
+                    notes_panel::type_note,
+                    notes_panel::render_notes_panel,
+                    notes_panel::highlight_selected_hex,
+                ).chain(),
+                (
+                    grid::toggle_grid.run_if(notes_panel::not_typing),
+                    grid::draw_grid,
+                    status_bar::measure,
+                    status_bar::draw_ruler,
+                    status_bar::change_scale,
+                    status_bar::update_status_bar,
+                ).chain(),
+                (asset_report::toggle_asset_report, asset_report::render_asset_report).chain(),
+                (
+                    minimap::render_minimap,
+                    minimap::update_minimap_views,
+                    minimap::jump_to_minimap,
+                ).chain(),
+                admin::deselect_tool,
             ).run_if(resource_exists::<AppLoaded>));
     }
 }
\ No newline at end of file
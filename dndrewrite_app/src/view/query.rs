@@ -21,6 +21,11 @@ impl<'w, 's> UIQuery<'w, 's> {
         self.window_query.iter().find(|w| w.1.focused)
     }
 
+    pub fn is_admin_focused(&self) -> bool {
+        let Some(windows) = self.windows.as_ref() else { return false; };
+        self.get_focused_window().is_some_and(|(window, _)| window == windows.admin_window)
+    }
+
     pub fn get_focused_window_mut(&mut self) -> Option<(Entity, Mut<Window>)> {
         self.window_query.iter_mut().find(|w| w.1.focused)
     }
@@ -31,4 +36,37 @@ impl<'w, 's> UIQuery<'w, 's> {
             _ => false
         })
     }
-}
\ No newline at end of file
+
+    fn window_camera(&self, admin: bool) -> Option<(&Transform, &Camera, &GlobalTransform)> {
+        let windows = self.windows.as_ref()?;
+        let window = if admin { windows.admin_window } else { windows.user_window };
+        self.cameras.iter()
+            .find(|(_, _, _, camera, _)| matches!(camera.target, RenderTarget::Window(WindowRef::Entity(e)) if e == window))
+            .map(|(_, transform, _, camera, global)| (transform.into_inner(), camera, global))
+    }
+
+    /// The part of the world the admin or the players' window shows.
+    pub fn visible_world_rect(&self, admin: bool) -> Option<Rect> {
+        let (_, camera, transform) = self.window_camera(admin)?;
+        let viewport = camera.logical_viewport_rect()?;
+        let a = camera.viewport_to_world_2d(transform, viewport.min)?;
+        let b = camera.viewport_to_world_2d(transform, viewport.max)?;
+        Some(Rect::from_corners(a, b))
+    }
+
+    /// Moves the camera of the admin or the players' window over the position.
+    pub fn center_on_world(&mut self, admin: bool, position: Vec2) {
+        let Some(windows) = self.windows.as_ref() else { return; };
+        let window = if admin { windows.admin_window } else { windows.user_window };
+        for (_, mut transform, _, camera, _) in &mut self.cameras {
+            if !matches!(camera.target, RenderTarget::Window(WindowRef::Entity(e)) if e == window) { continue; }
+            transform.translation.x = position.x;
+            transform.translation.y = position.y;
+        }
+    }
+
+    pub fn center_on(&mut self, admin: bool, hex: hexx::Hex) {
+        let position = self.layout.hex_to_world_pos(hex);
+        self.center_on_world(admin, position);
+    }
+}
@@ -8,11 +8,14 @@ pub struct Scale(pub(super) f32);
 #[derive(Resource, Default, Deref, DerefMut)]
 pub struct MouseLastPosition(pub(super) Vec2);
 
-#[derive(Resource, Deref, DerefMut)]
+#[derive(Resource, Default, Deref, DerefMut)]
 pub struct HexLayoutResource(pub(super) HexLayout);
 
 #[derive(Resource)]
 pub struct Windows {
     pub admin_window: Entity,
     pub user_window: Entity,
-}
\ No newline at end of file
+}
+/// Whether the hex outlines are drawn in the admin window.
+#[derive(Resource, Default, Deref, DerefMut)]
+pub struct GridVisible(pub(super) bool);
@@ -5,26 +5,48 @@ use bevy::prelude::*;
 #[derive(Component, Default)]
 pub struct ScrollingList {
     position: f32,
+    vertical: bool,
+}
+
+impl ScrollingList {
+    pub fn vertical() -> Self {
+        ScrollingList {
+            vertical: true,
+            ..default()
+        }
+    }
 }
 
 //Modified copy from https://bevyengine.org/examples/UI%20(User%20Interface)/ui/
 pub fn mouse_scroll(
     mut mouse_wheel_events: EventReader<MouseWheel>,
     mut query_list: Query<(&mut ScrollingList, &mut Style, &Parent, &Node)>,
-    query_node: Query<&Node>,
+    query_node: Query<(&Node, Option<&Interaction>)>,
 ) {
     for mouse_wheel_event in mouse_wheel_events.read() {
         for (mut scrolling_list, mut style, parent, list_node) in &mut query_list {
-            let items_width = list_node.size().x;
-            let container_width = query_node.get(parent.get()).unwrap().size().x;
-            let max_scroll = (items_width - container_width).max(0.) / 2.;
+            let (container_node, container_interaction) = query_node.get(parent.get()).unwrap();
+            //Containers that track the cursor only scroll while hovered
+            if container_interaction.is_some_and(|interaction| *interaction == Interaction::None) { continue; }
             let dx = match mouse_wheel_event.unit {
                 MouseScrollUnit::Line => mouse_wheel_event.y * 20.,
                 MouseScrollUnit::Pixel => mouse_wheel_event.y,
             };
-            scrolling_list.position += dx;
-            scrolling_list.position = scrolling_list.position.clamp(-max_scroll, max_scroll);
-            style.left = Val::Px(scrolling_list.position);
+            if scrolling_list.vertical {
+                let items_height = list_node.size().y;
+                let container_height = container_node.size().y;
+                let max_scroll = (items_height - container_height).max(0.);
+                scrolling_list.position += dx;
+                scrolling_list.position = scrolling_list.position.clamp(-max_scroll, 0.);
+                style.top = Val::Px(scrolling_list.position);
+            } else {
+                let items_width = list_node.size().x;
+                let container_width = container_node.size().x;
+                let max_scroll = (items_width - container_width).max(0.) / 2.;
+                scrolling_list.position += dx;
+                scrolling_list.position = scrolling_list.position.clamp(-max_scroll, max_scroll);
+                style.left = Val::Px(scrolling_list.position);
+            }
         }
     }
-}
\ No newline at end of file
+}
@@ -5,11 +5,14 @@ use bevy::math::Vec3;
 use bevy::prelude::*;
 use bevy::render::camera::RenderTarget;
 use bevy::render::texture;
+use bevy::render::view::RenderLayers;
 use bevy::ui::RelativeCursorPosition;
 use bevy::window::{PrimaryWindow, WindowRef};
 use hexx::Hex;
 use crate::app::admin_button::AdminButton;
-use crate::app::resources::{AdminButtonMarker, AdminMenus, AdminMenuStack, CurrentAdminMenu, UITracker};
+use crate::model::texture_tree::TextureLeaf;
+use crate::app::{asset_report, initiative_panel, minimap, notes_panel, painting, palette, status_bar, texture_search, tooltip};
+use crate::app::resources::{AdminButtonMarker, AdminMenus, AdminMenuStack, CurrentAdminMenu, SelectedTool, Stroke, TokenDrag, UITracker};
 use crate::model::id::Id;
 use crate::view::query::UIQuery;
 use super::*;
@@ -73,6 +76,8 @@ pub fn move_camera(
     time: Res<Time>,
     keys: Res<ButtonInput<KeyCode>>,
 ) {
+    //Ctrl combinations are shortcuts, Ctrl+S saves
+    if keys.any_pressed([KeyCode::ControlLeft, KeyCode::ControlRight]) { return; }
     let Some(mut entity) = ui.get_focused_camera() else { return };
     let mut transform = entity.1;
     let vel = 10_000. * time.delta().as_secs_f32();
@@ -95,11 +100,16 @@ pub fn get_clicked_hex(
     admin: bool,
 ) -> Option<Hex> {
     if !ui.mouse_button.just_pressed(MouseButton::Left) { return None; }
-    let windows = ui.windows?;
-    let admin_window = windows.admin_window;
-    ui.windows = Some(windows);
+    get_hovered_hex(&mut ui, admin)
+}
+
+pub fn get_hovered_hex(
+    ui: &mut UIQuery,
+    admin: bool,
+) -> Option<Hex> {
+    let admin_window = ui.windows.as_ref()?.admin_window;
     let (focused, window) = ui.get_focused_window()?;
-    let is_admin_window = focused != admin_window;
+    let is_admin_window = focused == admin_window;
     if is_admin_window != admin { return None; };
     let cursor = window.cursor_position()?;
     let camera_entity = ui.get_focused_camera()?;
@@ -109,6 +119,14 @@ pub fn get_clicked_hex(
     Some(ui.layout.world_pos_to_hex(pos))
 }
 
+/// Gizmos are editing aids, the players never see them.
+pub fn setup_gizmos(
+    mut config_store: ResMut<GizmoConfigStore>,
+) {
+    let (config, _) = config_store.config_mut::<DefaultGizmoConfigGroup>();
+    config.render_layers = RenderLayers::layer(layers::ADMIN_LAYER);
+}
+
 pub fn setup_ui(
     mut images: ResMut<Assets<Image>>,
     mut commands: Commands,
@@ -120,26 +138,26 @@ pub fn setup_ui(
         ..default()
     }).id();
     info!("Admin Window: {}", admin_window);
-    let admin_camera = commands.spawn(Camera2dBundle {
+    let admin_camera = commands.spawn((Camera2dBundle {
         camera: Camera {
             target: RenderTarget::Window(WindowRef::Entity(admin_window)),
             ..default()
         },
         ..default()
-    }).id();
+    }, layers::admin_camera_layers())).id();
     info!("Admin Camera: {}", admin_camera);
     let user_window = commands.spawn(Window {
         title: String::from("Faerûn"),
         ..default()
     }).id();
     info!("User Window: {}", user_window);
-    let user_camera = commands.spawn(Camera2dBundle {
+    let user_camera = commands.spawn((Camera2dBundle {
         camera: Camera {
             target: RenderTarget::Window(WindowRef::Entity(user_window)),
             ..default()
         },
         ..default()
-    }).id();
+    }, layers::user_camera_layers())).id();
     info!("User Camera: {}", user_camera);
     let scroll_bar = commands.spawn((NodeBundle {
         style: Style {
@@ -163,8 +181,12 @@ pub fn setup_ui(
         Interaction::default(),
         RelativeCursorPosition::default(),
         AdminButtonMarker(AdminButton {
-            texture: images.add(Image::transparent()),
+            texture: TextureLeaf {
+                image: images.add(Image::transparent()),
+                ..default()
+            },
             name: "BACK".to_string(),
+            tooltip: "Back".to_string(),
             on_click: Box::new(|id| id.init()),
             on_hover: Box::new(|mut window| window.cursor.icon = CursorIcon::Pointer)
         }.into())
@@ -184,24 +206,37 @@ pub fn setup_ui(
         },
         background_color: BackgroundColor(Color::BLACK),
         ..default()
-    }, Interaction::default())).push_children(&[scroll_bar]).id();
+    }, Interaction::default())).id();
+    let brush_panel = painting::spawn_brush_panel(&mut commands);
+    let search = texture_search::spawn_texture_search(&mut commands);
+    commands.entity(bar).push_children(&[brush_panel, search, scroll_bar]);
+    let palette = palette::spawn_palette(&mut commands);
     commands.spawn((NodeBundle {
         style: Style {
             display: Display::Flex,
-            flex_direction: FlexDirection::Row,
-            align_items: AlignItems::FlexEnd,
-            justify_content: JustifyContent::Center,
+            flex_direction: FlexDirection::Column,
+            align_items: AlignItems::Center,
+            justify_content: JustifyContent::FlexEnd,
             width: Val::Percent(100.),
             height: Val::Percent(100.),
             ..default()
         },
         ..default()
-    }, TargetCamera(admin_camera))).push_children(&[bar]);
+    }, TargetCamera(admin_camera))).push_children(&[palette, bar]);
+    initiative_panel::spawn_initiative_panel(&mut commands, admin_camera);
+    notes_panel::spawn_notes_panel(&mut commands, admin_camera);
+    status_bar::spawn_status_bar(&mut commands, admin_camera);
+    minimap::spawn_minimap(&mut commands, &mut images, admin_camera);
+    asset_report::spawn_asset_report(&mut commands, admin_camera);
+    tooltip::spawn_tooltip(&mut commands, admin_camera);
     commands.insert_resource(resources::Windows {
         admin_window,
         user_window,
     });
     commands.init_resource::<AdminMenuStack>();
+    commands.init_resource::<SelectedTool>();
+    commands.init_resource::<Stroke>();
+    commands.init_resource::<TokenDrag>();
     commands.init_resource::<AdminMenus>();
     commands.init_resource::<CurrentAdminMenu>();
 }
\ No newline at end of file